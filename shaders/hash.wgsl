@@ -0,0 +1,229 @@
+// requires: secp256k1.wgsl
+//
+// SHA-256 and RIPEMD-160 for the fixed message sizes of SEC1 public keys
+// and P2WPKH redeem scripts.
+//
+// Messages are passed as big-endian words: byte 0 is the top byte of word 0
+// and a partial last word is zero-filled. SHA-256 results are eight
+// big-endian words; RIPEMD-160 results are five words whose little-endian
+// bytes are the digest, so they can be stored to memory unchanged.
+//
+// No bindings; concatenate after secp256k1.wgsl (for bswap32) in front of
+// a kernel that needs it.
+
+var<private> SHA256_K : array<u32, 64> = array<u32, 64>(
+  0x428a2f98u, 0x71374491u, 0xb5c0fbcfu, 0xe9b5dba5u, 0x3956c25bu, 0x59f111f1u, 0x923f82a4u, 0xab1c5ed5u,
+  0xd807aa98u, 0x12835b01u, 0x243185beu, 0x550c7dc3u, 0x72be5d74u, 0x80deb1feu, 0x9bdc06a7u, 0xc19bf174u,
+  0xe49b69c1u, 0xefbe4786u, 0x0fc19dc6u, 0x240ca1ccu, 0x2de92c6fu, 0x4a7484aau, 0x5cb0a9dcu, 0x76f988dau,
+  0x983e5152u, 0xa831c66du, 0xb00327c8u, 0xbf597fc7u, 0xc6e00bf3u, 0xd5a79147u, 0x06ca6351u, 0x14292967u,
+  0x27b70a85u, 0x2e1b2138u, 0x4d2c6dfcu, 0x53380d13u, 0x650a7354u, 0x766a0abbu, 0x81c2c92eu, 0x92722c85u,
+  0xa2bfe8a1u, 0xa81a664bu, 0xc24b8b70u, 0xc76c51a3u, 0xd192e819u, 0xd6990624u, 0xf40e3585u, 0x106aa070u,
+  0x19a4c116u, 0x1e376c08u, 0x2748774cu, 0x34b0bcb5u, 0x391c0cb3u, 0x4ed8aa4au, 0x5b9cca4fu, 0x682e6ff3u,
+  0x748f82eeu, 0x78a5636fu, 0x84c87814u, 0x8cc70208u, 0x90befffau, 0xa4506cebu, 0xbef9a3f7u, 0xc67178f2u,
+);
+
+var<private> RMD_R : array<u32, 80> = array<u32, 80>(
+  0u, 1u, 2u, 3u, 4u, 5u, 6u, 7u, 8u, 9u, 10u, 11u, 12u, 13u, 14u, 15u,
+  7u, 4u, 13u, 1u, 10u, 6u, 15u, 3u, 12u, 0u, 9u, 5u, 2u, 14u, 11u, 8u,
+  3u, 10u, 14u, 4u, 9u, 15u, 8u, 1u, 2u, 7u, 0u, 6u, 13u, 11u, 5u, 12u,
+  1u, 9u, 11u, 10u, 0u, 8u, 12u, 4u, 13u, 3u, 7u, 15u, 14u, 5u, 6u, 2u,
+  4u, 0u, 5u, 9u, 7u, 12u, 2u, 10u, 14u, 1u, 3u, 8u, 11u, 6u, 15u, 13u,
+);
+
+var<private> RMD_RP : array<u32, 80> = array<u32, 80>(
+  5u, 14u, 7u, 0u, 9u, 2u, 11u, 4u, 13u, 6u, 15u, 8u, 1u, 10u, 3u, 12u,
+  6u, 11u, 3u, 7u, 0u, 13u, 5u, 10u, 14u, 15u, 8u, 12u, 4u, 9u, 1u, 2u,
+  15u, 5u, 1u, 3u, 7u, 14u, 6u, 9u, 11u, 8u, 12u, 2u, 10u, 0u, 4u, 13u,
+  8u, 6u, 4u, 1u, 3u, 11u, 15u, 0u, 5u, 12u, 2u, 13u, 9u, 7u, 10u, 14u,
+  12u, 15u, 10u, 4u, 1u, 5u, 8u, 7u, 6u, 2u, 13u, 14u, 0u, 3u, 9u, 11u,
+);
+
+var<private> RMD_S : array<u32, 80> = array<u32, 80>(
+  11u, 14u, 15u, 12u, 5u, 8u, 7u, 9u, 11u, 13u, 14u, 15u, 6u, 7u, 9u, 8u,
+  7u, 6u, 8u, 13u, 11u, 9u, 7u, 15u, 7u, 12u, 15u, 9u, 11u, 7u, 13u, 12u,
+  11u, 13u, 6u, 7u, 14u, 9u, 13u, 15u, 14u, 8u, 13u, 6u, 5u, 12u, 7u, 5u,
+  11u, 12u, 14u, 15u, 14u, 15u, 9u, 8u, 9u, 14u, 5u, 6u, 8u, 6u, 5u, 12u,
+  9u, 15u, 5u, 11u, 6u, 8u, 13u, 12u, 5u, 12u, 13u, 14u, 11u, 8u, 5u, 6u,
+);
+
+var<private> RMD_SP : array<u32, 80> = array<u32, 80>(
+  8u, 9u, 9u, 11u, 13u, 15u, 15u, 5u, 7u, 7u, 8u, 11u, 14u, 14u, 12u, 6u,
+  9u, 13u, 15u, 7u, 12u, 8u, 9u, 11u, 7u, 7u, 12u, 7u, 6u, 15u, 13u, 11u,
+  9u, 7u, 15u, 11u, 8u, 6u, 6u, 14u, 12u, 13u, 5u, 14u, 13u, 13u, 7u, 5u,
+  15u, 5u, 8u, 11u, 14u, 14u, 6u, 14u, 6u, 9u, 12u, 9u, 12u, 5u, 15u, 8u,
+  8u, 5u, 12u, 9u, 12u, 5u, 14u, 6u, 8u, 13u, 6u, 5u, 15u, 13u, 11u, 11u,
+);
+
+var<private> RMD_K : array<u32, 5> =
+  array<u32, 5>(0x00000000u, 0x5a827999u, 0x6ed9eba1u, 0x8f1bbcdcu, 0xa953fd4eu);
+var<private> RMD_KP : array<u32, 5> =
+  array<u32, 5>(0x50a28be6u, 0x5c4dd124u, 0x6d703ef3u, 0x7a6d76e9u, 0x00000000u);
+
+fn rotr(x: u32, n: u32) -> u32 {
+  return (x >> n) | (x << (32u - n));
+}
+
+fn rotl(x: u32, n: u32) -> u32 {
+  return (x << n) | (x >> (32u - n));
+}
+
+fn sha256_iv() -> array<u32, 8> {
+  return array<u32, 8>(
+    0x6a09e667u, 0xbb67ae85u, 0x3c6ef372u, 0xa54ff53au,
+    0x510e527fu, 0x9b05688cu, 0x1f83d9abu, 0x5be0cd19u,
+  );
+}
+
+fn sha256_compress(state_in: array<u32, 8>, block_in: array<u32, 16>) -> array<u32, 8> {
+  var state = state_in;
+  var block = block_in;
+  var w : array<u32, 64>;
+  for (var i = 0u; i < 16u; i = i + 1u) {
+    w[i] = block[i];
+  }
+  for (var i = 16u; i < 64u; i = i + 1u) {
+    let a = w[i - 15u];
+    let b = w[i - 2u];
+    let s0 = rotr(a, 7u) ^ rotr(a, 18u) ^ (a >> 3u);
+    let s1 = rotr(b, 17u) ^ rotr(b, 19u) ^ (b >> 10u);
+    w[i] = w[i - 16u] + s0 + w[i - 7u] + s1;
+  }
+
+  var a = state[0];
+  var b = state[1];
+  var c = state[2];
+  var d = state[3];
+  var e = state[4];
+  var f = state[5];
+  var g = state[6];
+  var h = state[7];
+  for (var i = 0u; i < 64u; i = i + 1u) {
+    let s1 = rotr(e, 6u) ^ rotr(e, 11u) ^ rotr(e, 25u);
+    let ch = (e & f) ^ (~e & g);
+    let t1 = h + s1 + ch + SHA256_K[i] + w[i];
+    let s0 = rotr(a, 2u) ^ rotr(a, 13u) ^ rotr(a, 22u);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t2 = s0 + maj;
+    h = g;
+    g = f;
+    f = e;
+    e = d + t1;
+    d = c;
+    c = b;
+    b = a;
+    a = t1 + t2;
+  }
+  state[0] += a;
+  state[1] += b;
+  state[2] += c;
+  state[3] += d;
+  state[4] += e;
+  state[5] += f;
+  state[6] += g;
+  state[7] += h;
+  return state;
+}
+
+// SHA-256 of a 22-byte message (P2WPKH redeem script), one block.
+fn sha256_22(m_in: array<u32, 6>) -> array<u32, 8> {
+  var m = m_in;
+  var block : array<u32, 16>;
+  for (var i = 0u; i < 5u; i = i + 1u) {
+    block[i] = m[i];
+  }
+  block[5] = (m[5] & 0xffff0000u) | 0x00008000u;
+  block[15] = 22u * 8u;
+  return sha256_compress(sha256_iv(), block);
+}
+
+// SHA-256 of a 33-byte message (compressed public key), one block.
+fn sha256_33(m_in: array<u32, 9>) -> array<u32, 8> {
+  var m = m_in;
+  var block : array<u32, 16>;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    block[i] = m[i];
+  }
+  block[8] = (m[8] & 0xff000000u) | 0x00800000u;
+  block[15] = 33u * 8u;
+  return sha256_compress(sha256_iv(), block);
+}
+
+// SHA-256 of a 65-byte message (uncompressed public key), two blocks.
+fn sha256_65(m_in: array<u32, 17>) -> array<u32, 8> {
+  var m = m_in;
+  var block : array<u32, 16>;
+  for (var i = 0u; i < 16u; i = i + 1u) {
+    block[i] = m[i];
+  }
+  let state = sha256_compress(sha256_iv(), block);
+  var tail : array<u32, 16>;
+  tail[0] = (m[16] & 0xff000000u) | 0x00800000u;
+  tail[15] = 65u * 8u;
+  return sha256_compress(state, tail);
+}
+
+fn rmd_f(j: u32, x: u32, y: u32, z: u32) -> u32 {
+  let round = j / 16u;
+  if (round == 0u) { return x ^ y ^ z; }
+  if (round == 1u) { return (x & y) | (~x & z); }
+  if (round == 2u) { return (x | ~y) ^ z; }
+  if (round == 3u) { return (x & z) | (y & ~z); }
+  return x ^ (y | ~z);
+}
+
+// RIPEMD-160 of a 32-byte message given as SHA-256 output words.
+fn ripemd160_32(d_in: array<u32, 8>) -> array<u32, 5> {
+  var d = d_in;
+  var x : array<u32, 16>;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    x[i] = bswap32(d[i]);
+  }
+  x[8] = 0x80u;
+  x[14] = 256u;
+
+  let h = array<u32, 5>(0x67452301u, 0xefcdab89u, 0x98badcfeu, 0x10325476u, 0xc3d2e1f0u);
+  var al = h[0];
+  var bl = h[1];
+  var cl = h[2];
+  var dl = h[3];
+  var el = h[4];
+  var ar = h[0];
+  var br = h[1];
+  var cr = h[2];
+  var dr = h[3];
+  var er = h[4];
+  for (var j = 0u; j < 80u; j = j + 1u) {
+    var t = rotl(al + rmd_f(j, bl, cl, dl) + x[RMD_R[j]] + RMD_K[j / 16u], RMD_S[j]) + el;
+    al = el;
+    el = dl;
+    dl = rotl(cl, 10u);
+    cl = bl;
+    bl = t;
+
+    t = rotl(ar + rmd_f(79u - j, br, cr, dr) + x[RMD_RP[j]] + RMD_KP[j / 16u], RMD_SP[j]) + er;
+    ar = er;
+    er = dr;
+    dr = rotl(cr, 10u);
+    cr = br;
+    br = t;
+  }
+  return array<u32, 5>(
+    h[1] + cl + dr,
+    h[2] + dl + er,
+    h[3] + el + ar,
+    h[4] + al + br,
+    h[0] + bl + cr,
+  );
+}
+
+fn hash160_22(m: array<u32, 6>) -> array<u32, 5> {
+  return ripemd160_32(sha256_22(m));
+}
+
+fn hash160_33(m: array<u32, 9>) -> array<u32, 5> {
+  return ripemd160_32(sha256_33(m));
+}
+
+fn hash160_65(m: array<u32, 17>) -> array<u32, 5> {
+  return ripemd160_32(sha256_65(m));
+}
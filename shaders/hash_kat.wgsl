@@ -0,0 +1,48 @@
+// requires: secp256k1.wgsl hash.wgsl
+//
+// Known-answer harness for hash.wgsl. Each input message is 18 words: its
+// length in bytes (22, 33 or 65) followed by 17 big-endian message words. Each
+// output record is 13 words: the SHA-256 state followed by the HASH160.
+
+@group(0) @binding(0)
+var<storage, read> msgs : array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> digests : array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) gid : vec3<u32>) {
+  let idx = gid.x;
+  if ((idx + 1u) * 18u > arrayLength(&msgs)) { return; }
+  let base = idx * 18u;
+
+  var sha : array<u32, 8>;
+  if (msgs[base] == 22u) {
+    var m : array<u32, 6>;
+    for (var i = 0u; i < 6u; i = i + 1u) {
+      m[i] = msgs[base + 1u + i];
+    }
+    sha = sha256_22(m);
+  } else if (msgs[base] == 33u) {
+    var m : array<u32, 9>;
+    for (var i = 0u; i < 9u; i = i + 1u) {
+      m[i] = msgs[base + 1u + i];
+    }
+    sha = sha256_33(m);
+  } else {
+    var m : array<u32, 17>;
+    for (var i = 0u; i < 17u; i = i + 1u) {
+      m[i] = msgs[base + 1u + i];
+    }
+    sha = sha256_65(m);
+  }
+  var h = ripemd160_32(sha);
+
+  let out = idx * 13u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    digests[out + i] = sha[i];
+  }
+  for (var i = 0u; i < 5u; i = i + 1u) {
+    digests[out + 8u + i] = h[i];
+  }
+}
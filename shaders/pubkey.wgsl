@@ -0,0 +1,260 @@
+// requires: secp256k1.wgsl hash.wgsl
+//
+// Compressed public keys, or their HASH160, for a run of consecutive private
+// keys.
+//
+// The host computes base = (start + lo) * G and uploads 2^i * G for
+// i < 32. Invocation idx in [lo, hi) computes base + (idx - lo) * G with at
+// most 32 mixed additions; invocations outside that window correspond to keys
+// outside 1..n-1 and write an all-zero record.
+//
+// With ec.mode == MODE_PUBKEY each record is 9 words: word 0 holds the
+// 0x02/0x03 prefix in its low byte, words 1..8 hold x so that their
+// little-endian bytes read big-endian. With ec.mode == MODE_HASH160 each
+// record is the 5-word HASH160 of that compressed key.
+//
+// The `find` entry point writes no records: it hashes the compressed and/or
+// uncompressed key, as ec.forms selects, and with FORM_NESTED_SEGWIT also the
+// P2WPKH redeem script of the compressed key's HASH160. It looks each HASH160
+// up in the sorted `wanted` table and appends the indices that match to `hits` through an
+// atomic counter. The counter keeps counting past the end of hits.idx so
+// the host can tell that indices were dropped. Every tile of a batch shares
+// `hits`, so indices are offset by ec.first to count from the batch start.
+
+struct EcParams {
+  base_x : array<vec4<u32>, 2>,
+  base_y : array<vec4<u32>, 2>,
+  n      : u32,
+  lo     : u32,
+  hi     : u32,
+  mode   : u32,
+  // FORM_* bits, for `find`.
+  forms  : u32,
+  // Index of this tile's first key in the batch, for `find`.
+  first  : u32,
+};
+
+@group(0) @binding(0)
+var<uniform> ec : EcParams;
+
+// 32 affine points, 16 words each: x limbs then y limbs (little-endian).
+@group(0) @binding(1)
+var<storage, read> g_pow2 : array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> records : array<u32>;
+
+struct Hits {
+  count : atomic<u32>,
+  idx   : array<u32>,
+};
+
+@group(0) @binding(3)
+var<storage, read_write> hits : Hits;
+
+// Target HASH160s for `find`, 5 little-endian words each, sorted by comparing
+// those words in order.
+@group(0) @binding(4)
+var<storage, read> wanted : array<u32>;
+
+const MODE_PUBKEY : u32 = 0u;
+const MODE_HASH160 : u32 = 1u;
+const FORM_COMPRESSED : u32 = 1u;
+const FORM_UNCOMPRESSED : u32 = 2u;
+const FORM_NESTED_SEGWIT : u32 = 4u;
+const PUBKEY_WORDS : u32 = 9u;
+const HASH160_WORDS : u32 = 5u;
+
+fn vec_pair_to_fe(v: array<vec4<u32>, 2>) -> Fe {
+  return Fe(v[0].x, v[0].y, v[0].z, v[0].w, v[1].x, v[1].y, v[1].z, v[1].w);
+}
+
+fn table_x(i: u32) -> Fe {
+  var x : Fe;
+  for (var k = 0u; k < 8u; k = k + 1u) {
+    x[k] = g_pow2[i * 16u + k];
+  }
+  return x;
+}
+
+fn table_y(i: u32) -> Fe {
+  var y : Fe;
+  for (var k = 0u; k < 8u; k = k + 1u) {
+    y[k] = g_pow2[i * 16u + 8u + k];
+  }
+  return y;
+}
+
+fn write_pubkey(idx: u32, prefix: u32, x_in: Fe) {
+  var x = x_in;
+  let base = idx * PUBKEY_WORDS;
+  records[base] = prefix;
+  for (var k = 0u; k < 8u; k = k + 1u) {
+    records[base + 1u + k] = bswap32(x[7u - k]);
+  }
+}
+
+// SEC1 compressed key packed as big-endian message words for hash160_33.
+fn compressed_message(prefix: u32, x_in: Fe) -> array<u32, 9> {
+  var x = x_in;
+  var m : array<u32, 9>;
+  m[0] = (prefix << 24u) | (x[7] >> 8u);
+  for (var k = 1u; k < 8u; k = k + 1u) {
+    m[k] = (x[8u - k] << 24u) | (x[7u - k] >> 8u);
+  }
+  m[8] = x[0] << 24u;
+  return m;
+}
+
+// SEC1 uncompressed key, 0x04 || x || y, packed as big-endian message words
+// for hash160_65.
+fn uncompressed_message(x_in: Fe, y_in: Fe) -> array<u32, 17> {
+  var x = x_in;
+  var y = y_in;
+  var xy : array<u32, 16>;
+  for (var k = 0u; k < 8u; k = k + 1u) {
+    xy[k] = x[7u - k];
+    xy[8u + k] = y[7u - k];
+  }
+  var m : array<u32, 17>;
+  m[0] = (4u << 24u) | (xy[0] >> 8u);
+  for (var k = 1u; k < 16u; k = k + 1u) {
+    m[k] = (xy[k - 1u] << 24u) | (xy[k] >> 8u);
+  }
+  m[16] = xy[15] << 24u;
+  return m;
+}
+
+// Redeem script 0x00 0x14 || h packed as big-endian message words for
+// hash160_22; h is a HASH160 as returned by hash160_33.
+fn redeem_script_message(h_in: array<u32, 5>) -> array<u32, 6> {
+  var h = h_in;
+  var be : array<u32, 5>;
+  for (var k = 0u; k < 5u; k = k + 1u) {
+    be[k] = bswap32(h[k]);
+  }
+  var m : array<u32, 6>;
+  m[0] = 0x00140000u | (be[0] >> 16u);
+  for (var k = 1u; k < 5u; k = k + 1u) {
+    m[k] = (be[k - 1u] << 16u) | (be[k] >> 16u);
+  }
+  m[5] = be[4] << 16u;
+  return m;
+}
+
+fn write_hash160(idx: u32, h_in: array<u32, 5>) {
+  var h = h_in;
+  let base = idx * HASH160_WORDS;
+  for (var k = 0u; k < 5u; k = k + 1u) {
+    records[base + k] = h[k];
+  }
+}
+
+// Record for key idx; prefix 0 marks a key outside 1..n-1.
+fn write_record(idx: u32, prefix: u32, x: Fe) {
+  if (ec.mode == MODE_HASH160) {
+    if (prefix == 0u) {
+      write_hash160(idx, array<u32, 5>(0u, 0u, 0u, 0u, 0u));
+    } else {
+      write_hash160(idx, hash160_33(compressed_message(prefix, x)));
+    }
+  } else {
+    write_pubkey(idx, prefix, x);
+  }
+}
+
+// Binary search of `wanted` for h.
+fn is_wanted(h_in: array<u32, 5>) -> bool {
+  var h = h_in;
+  var lo = 0u;
+  var hi = arrayLength(&wanted) / HASH160_WORDS;
+  while (lo < hi) {
+    let mid = (lo + hi) / 2u;
+    var ord = 0;
+    for (var k = 0u; k < HASH160_WORDS; k = k + 1u) {
+      let w = wanted[mid * HASH160_WORDS + k];
+      if (h[k] != w) {
+        ord = select(1, -1, h[k] < w);
+        break;
+      }
+    }
+    if (ord == 0) {
+      return true;
+    }
+    if (ord < 0) {
+      hi = mid;
+    } else {
+      lo = mid + 1u;
+    }
+  }
+  return false;
+}
+
+struct Candidate {
+  // 0x02/0x03, or 0 when the key is outside 1..n-1.
+  prefix : u32,
+  x      : Fe,
+  y      : Fe,
+};
+
+fn candidate(idx: u32) -> Candidate {
+  if (idx < ec.lo || idx >= ec.hi) {
+    return Candidate(0u, fe_zero(), fe_zero());
+  }
+
+  let j = idx - ec.lo;
+  var p = jac_from_affine(vec_pair_to_fe(ec.base_x), vec_pair_to_fe(ec.base_y));
+  for (var i = 0u; i < 32u; i = i + 1u) {
+    if (((j >> i) & 1u) == 1u) {
+      p = jac_add_affine(p, table_x(i), table_y(i));
+    }
+  }
+
+  if (jac_is_infinity(p)) {
+    return Candidate(0u, fe_zero(), fe_zero());
+  }
+  var xy = jac_to_affine(p);
+  var y = xy[1];
+  return Candidate(2u | (y[0] & 1u), xy[0], y);
+}
+
+@compute @workgroup_size(256)
+fn main(
+  @builtin(global_invocation_id) gid : vec3<u32>,
+  @builtin(num_workgroups) groups : vec3<u32>,
+) {
+  let idx = gid.x + gid.y * groups.x * 256u;
+  if (idx >= ec.n) { return; }
+  let c = candidate(idx);
+  write_record(idx, c.prefix, c.x);
+}
+
+@compute @workgroup_size(256)
+fn find(
+  @builtin(global_invocation_id) gid : vec3<u32>,
+  @builtin(num_workgroups) groups : vec3<u32>,
+) {
+  let idx = gid.x + gid.y * groups.x * 256u;
+  if (idx >= ec.n) { return; }
+  let c = candidate(idx);
+  if (c.prefix == 0u) { return; }
+
+  var found = false;
+  if ((ec.forms & FORM_COMPRESSED) != 0u) {
+    let h = hash160_33(compressed_message(c.prefix, c.x));
+    found = is_wanted(h);
+    if (!found && (ec.forms & FORM_NESTED_SEGWIT) != 0u) {
+      found = is_wanted(hash160_22(redeem_script_message(h)));
+    }
+  }
+  if (!found && (ec.forms & FORM_UNCOMPRESSED) != 0u) {
+    found = is_wanted(hash160_65(uncompressed_message(c.x, c.y)));
+  }
+  if (!found) {
+    return;
+  }
+  let slot = atomicAdd(&hits.count, 1u);
+  if (slot < arrayLength(&hits.idx)) {
+    hits.idx[slot] = ec.first + idx;
+  }
+}
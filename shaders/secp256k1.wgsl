@@ -0,0 +1,338 @@
+// secp256k1 field and group arithmetic.
+//
+// Field elements and scalars are array<u32, 8> in little-endian limb order
+// (limb 0 least significant), matching U256::to_le_words on the host.
+// Points are Jacobian (X, Y, Z) with Z == 0 meaning infinity.
+//
+// This file has no bindings or entry points; kernels are concatenated after it.
+
+alias Fe = array<u32, 8>;
+
+struct Jac {
+  x : Fe,
+  y : Fe,
+  z : Fe,
+};
+
+// p = 2^256 - 2^32 - 977
+const P = Fe(
+  0xFFFFFC2Fu, 0xFFFFFFFEu, 0xFFFFFFFFu, 0xFFFFFFFFu,
+  0xFFFFFFFFu, 0xFFFFFFFFu, 0xFFFFFFFFu, 0xFFFFFFFFu
+);
+
+// 2^256 mod p = 2^32 + 977
+const P_C_LO : u32 = 977u;
+
+// (sum, carry) of a + b + carry_in
+fn adc(a: u32, b: u32, carry_in: u32) -> vec2<u32> {
+  let s1 = a + b;
+  let c1 = select(0u, 1u, s1 < a);
+  let s2 = s1 + carry_in;
+  let c2 = select(0u, 1u, s2 < s1);
+  return vec2<u32>(s2, c1 + c2);
+}
+
+// (difference, borrow) of a - b - borrow_in
+fn sbb(a: u32, b: u32, borrow_in: u32) -> vec2<u32> {
+  let d1 = a - b;
+  let b1 = select(0u, 1u, a < b);
+  let d2 = d1 - borrow_in;
+  let b2 = select(0u, 1u, d1 < borrow_in);
+  return vec2<u32>(d2, b1 + b2);
+}
+
+// Full 32x32 -> 64-bit product as (lo, hi), built from 16-bit halves.
+fn mul32(a: u32, b: u32) -> vec2<u32> {
+  let a0 = a & 0xFFFFu;
+  let a1 = a >> 16u;
+  let b0 = b & 0xFFFFu;
+  let b1 = b >> 16u;
+  let p00 = a0 * b0;
+  let p01 = a0 * b1;
+  let p10 = a1 * b0;
+  let p11 = a1 * b1;
+  let mid = p01 + (p00 >> 16u);
+  let mid2 = (mid & 0xFFFFu) + p10;
+  let lo = (mid2 << 16u) | (p00 & 0xFFFFu);
+  let hi = p11 + (mid >> 16u) + (mid2 >> 16u);
+  return vec2<u32>(lo, hi);
+}
+
+fn fe_zero() -> Fe {
+  return Fe(0u, 0u, 0u, 0u, 0u, 0u, 0u, 0u);
+}
+
+fn fe_one() -> Fe {
+  return Fe(1u, 0u, 0u, 0u, 0u, 0u, 0u, 0u);
+}
+
+fn fe_is_zero(a_in: Fe) -> bool {
+  var a = a_in;
+  var acc = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    acc = acc | a[i];
+  }
+  return acc == 0u;
+}
+
+fn fe_eq(a_in: Fe, b_in: Fe) -> bool {
+  var a = a_in;
+  var b = b_in;
+  var acc = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    acc = acc | (a[i] ^ b[i]);
+  }
+  return acc == 0u;
+}
+
+// a >= p, for a < 2^256
+fn fe_ge_p(a_in: Fe) -> bool {
+  var a = a_in;
+  var p = P;
+  for (var k = 0u; k < 8u; k = k + 1u) {
+    let i = 7u - k;
+    if (a[i] != p[i]) {
+      return a[i] > p[i];
+    }
+  }
+  return true;
+}
+
+// Subtract p from a 257-bit value (a + carry * 2^256), assuming it is < 2p.
+fn fe_sub_p(a_in: Fe) -> Fe {
+  var a = a_in;
+  var p = P;
+  var r : Fe;
+  var borrow = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    let d = sbb(a[i], p[i], borrow);
+    r[i] = d.x;
+    borrow = d.y;
+  }
+  return r;
+}
+
+fn fe_add(a_in: Fe, b_in: Fe) -> Fe {
+  var a = a_in;
+  var b = b_in;
+  var r : Fe;
+  var carry = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    let s = adc(a[i], b[i], carry);
+    r[i] = s.x;
+    carry = s.y;
+  }
+  if (carry != 0u || fe_ge_p(r)) {
+    r = fe_sub_p(r);
+  }
+  return r;
+}
+
+fn fe_sub(a_in: Fe, b_in: Fe) -> Fe {
+  var a = a_in;
+  var b = b_in;
+  var r : Fe;
+  var borrow = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    let d = sbb(a[i], b[i], borrow);
+    r[i] = d.x;
+    borrow = d.y;
+  }
+  if (borrow != 0u) {
+    var p = P;
+    var carry = 0u;
+    for (var i = 0u; i < 8u; i = i + 1u) {
+      let s = adc(r[i], p[i], carry);
+      r[i] = s.x;
+      carry = s.y;
+    }
+  }
+  return r;
+}
+
+fn fe_neg(a: Fe) -> Fe {
+  return fe_sub(fe_zero(), a);
+}
+
+fn fe_double(a: Fe) -> Fe {
+  return fe_add(a, a);
+}
+
+fn fe_mul(a_in: Fe, b_in: Fe) -> Fe {
+  var a = a_in;
+  var b = b_in;
+
+  // 512-bit schoolbook product
+  var w : array<u32, 16>;
+  for (var i = 0u; i < 16u; i = i + 1u) {
+    w[i] = 0u;
+  }
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    var carry = 0u;
+    for (var j = 0u; j < 8u; j = j + 1u) {
+      let m = mul32(a[i], b[j]);
+      let s1 = adc(w[i + j], m.x, 0u);
+      let s2 = adc(s1.x, carry, 0u);
+      w[i + j] = s2.x;
+      carry = m.y + s1.y + s2.y;
+    }
+    w[i + 8u] = carry;
+  }
+
+  // Fold the high half: H * 2^256 == H * (2^32 + 977) (mod p).
+  var r : array<u32, 9>;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    r[i] = w[i];
+  }
+  r[8] = 0u;
+  var carry = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    let m = mul32(w[i + 8u], P_C_LO);
+    let s1 = adc(r[i], m.x, carry);
+    r[i] = s1.x;
+    carry = m.y + s1.y;
+  }
+  r[8] = carry;
+  carry = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    let s = adc(r[i + 1u], w[i + 8u], carry);
+    r[i + 1u] = s.x;
+    carry = s.y;
+  }
+
+  // Top is now r[8] + carry * 2^32 (< 2^33); fold it the same way.
+  let t0 = r[8];
+  let t1 = carry;
+  var add : array<u32, 4>;
+  let m0 = mul32(t0, P_C_LO);
+  let m1 = mul32(t1, P_C_LO);
+  // t0 * 977 + t0 * 2^32 + t1 * 977 * 2^32 + t1 * 2^64
+  add[0] = m0.x;
+  let a1 = adc(m0.y, t0, 0u);
+  let a1b = adc(a1.x, m1.x, 0u);
+  add[1] = a1b.x;
+  let a2 = adc(m1.y, t1, a1.y + a1b.y);
+  add[2] = a2.x;
+  add[3] = a2.y;
+
+  var out : Fe;
+  carry = 0u;
+  for (var i = 0u; i < 8u; i = i + 1u) {
+    var addend = 0u;
+    if (i < 4u) {
+      addend = add[i];
+    }
+    let s = adc(r[i], addend, carry);
+    out[i] = s.x;
+    carry = s.y;
+  }
+  if (carry != 0u) {
+    // out + 2^256 == out + 2^32 + 977; out is tiny here, so no further carry.
+    let s0 = adc(out[0], P_C_LO, 0u);
+    out[0] = s0.x;
+    var c = s0.y + 1u;
+    for (var i = 1u; i < 8u; i = i + 1u) {
+      let s = adc(out[i], c, 0u);
+      out[i] = s.x;
+      c = s.y;
+    }
+  }
+  if (fe_ge_p(out)) {
+    out = fe_sub_p(out);
+  }
+  return out;
+}
+
+fn fe_sqr(a: Fe) -> Fe {
+  return fe_mul(a, a);
+}
+
+// a^(p-2) by square-and-multiply over the fixed exponent.
+fn fe_inv(a: Fe) -> Fe {
+  // p - 2, little-endian limbs
+  var e = Fe(
+    0xFFFFFC2Du, 0xFFFFFFFEu, 0xFFFFFFFFu, 0xFFFFFFFFu,
+    0xFFFFFFFFu, 0xFFFFFFFFu, 0xFFFFFFFFu, 0xFFFFFFFFu
+  );
+  var r = fe_one();
+  for (var k = 0u; k < 256u; k = k + 1u) {
+    let bit = 255u - k;
+    r = fe_sqr(r);
+    if (((e[bit / 32u] >> (bit % 32u)) & 1u) == 1u) {
+      r = fe_mul(r, a);
+    }
+  }
+  return r;
+}
+
+fn jac_infinity() -> Jac {
+  return Jac(fe_one(), fe_one(), fe_zero());
+}
+
+fn jac_from_affine(x: Fe, y: Fe) -> Jac {
+  return Jac(x, y, fe_one());
+}
+
+fn jac_is_infinity(p: Jac) -> bool {
+  return fe_is_zero(p.z);
+}
+
+// dbl-2009-l (a = 0)
+fn jac_double(p: Jac) -> Jac {
+  if (jac_is_infinity(p) || fe_is_zero(p.y)) {
+    return jac_infinity();
+  }
+  let a = fe_sqr(p.x);
+  let b = fe_sqr(p.y);
+  let c = fe_sqr(b);
+  let xb = fe_add(p.x, b);
+  let d = fe_double(fe_sub(fe_sub(fe_sqr(xb), a), c));
+  let e = fe_add(fe_double(a), a);
+  let f = fe_sqr(e);
+  let x3 = fe_sub(f, fe_double(d));
+  let c8 = fe_double(fe_double(fe_double(c)));
+  let y3 = fe_sub(fe_mul(e, fe_sub(d, x3)), c8);
+  let z3 = fe_double(fe_mul(p.y, p.z));
+  return Jac(x3, y3, z3);
+}
+
+// madd-2007-bl: Jacobian + affine, handling infinity, doubling and P + (-P).
+fn jac_add_affine(p: Jac, x2: Fe, y2: Fe) -> Jac {
+  if (jac_is_infinity(p)) {
+    return jac_from_affine(x2, y2);
+  }
+  let z1z1 = fe_sqr(p.z);
+  let u2 = fe_mul(x2, z1z1);
+  let s2 = fe_mul(y2, fe_mul(p.z, z1z1));
+  let h = fe_sub(u2, p.x);
+  let r = fe_double(fe_sub(s2, p.y));
+  if (fe_is_zero(h)) {
+    if (fe_is_zero(r)) {
+      return jac_double(p);
+    }
+    return jac_infinity();
+  }
+  let hh = fe_sqr(h);
+  let i = fe_double(fe_double(hh));
+  let j = fe_mul(h, i);
+  let v = fe_mul(p.x, i);
+  let x3 = fe_sub(fe_sub(fe_sqr(r), j), fe_double(v));
+  let y3 = fe_sub(fe_mul(r, fe_sub(v, x3)), fe_double(fe_mul(p.y, j)));
+  let zh = fe_add(p.z, h);
+  let z3 = fe_sub(fe_sub(fe_sqr(zh), z1z1), hh);
+  return Jac(x3, y3, z3);
+}
+
+// Affine (x, y) of a finite Jacobian point, packed as array<Fe, 2>.
+fn jac_to_affine(p: Jac) -> array<Fe, 2> {
+  let zi = fe_inv(p.z);
+  let zi2 = fe_sqr(zi);
+  let x = fe_mul(p.x, zi2);
+  let y = fe_mul(p.y, fe_mul(zi2, zi));
+  return array<Fe, 2>(x, y);
+}
+
+// Byte-swap so that little-endian storage of the word yields big-endian bytes.
+fn bswap32(v: u32) -> u32 {
+  return (v >> 24u) | ((v >> 8u) & 0xFF00u) | ((v << 8u) & 0xFF0000u) | (v << 24u);
+}
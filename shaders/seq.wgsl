@@ -28,8 +28,11 @@ fn add_with_carry(a: u32, b: u32, carry_in: u32) -> vec2<u32> {
 }
 
 @compute @workgroup_size(256)
-fn main(@builtin(global_invocation_id) gid : vec3<u32>) {
-  let idx = gid.x;
+fn main(
+  @builtin(global_invocation_id) gid : vec3<u32>,
+  @builtin(num_workgroups) groups : vec3<u32>,
+) {
+  let idx = gid.x + gid.y * groups.x * 256u;
   if (idx >= params.n) { return; }
 
   // Start limbs in LE
@@ -0,0 +1,594 @@
+//! Address, key and hash encoding helpers.
+
+use crate::chain::Chain;
+use anyhow::{Result, anyhow};
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{Bech32, Fe32, Hrp};
+use ripemd::Ripemd160;
+use secp256k1::{Scalar, Secp256k1, SecretKey, Verification, XOnlyPublicKey};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/// Decode a P2PKH address of `chain` into the HASH160 it commits to.
+pub fn decode_p2pkh_to_hash160(addr: &str, chain: &Chain) -> Result<[u8; 20]> {
+    let (version, h) = decode_base58_hash160(addr)?;
+    if version != chain.p2pkh_version {
+        return Err(anyhow!(
+            "Not a P2PKH address of this chain (version 0x{version:02x}, expected 0x{:02x})",
+            chain.p2pkh_version
+        ));
+    }
+    Ok(h)
+}
+
+/// Decode a P2SH address of `chain` into the HASH160 of the script it
+/// commits to.
+pub fn decode_p2sh_to_hash160(addr: &str, chain: &Chain) -> Result<[u8; 20]> {
+    let (version, h) = decode_base58_hash160(addr)?;
+    if version != chain.p2sh_version {
+        return Err(anyhow!(
+            "Not a P2SH address of this chain (version 0x{version:02x}, expected 0x{:02x})",
+            chain.p2sh_version
+        ));
+    }
+    Ok(h)
+}
+
+/// Version byte and HASH160 of a Base58Check address.
+fn decode_base58_hash160(addr: &str) -> Result<(u8, [u8; 20])> {
+    let raw = bs58::decode(addr).into_vec()?;
+    if raw.len() < 25 {
+        return Err(anyhow!("Invalid Base58Check length"));
+    }
+    let (payload, checksum) = raw.split_at(raw.len() - 4);
+    let checksum_expected = Sha256::digest(Sha256::digest(payload));
+    if &checksum_expected[..4] != checksum {
+        return Err(anyhow!("Invalid Base58Check checksum"));
+    }
+    if payload.len() != 1 + 20 {
+        return Err(anyhow!("Invalid Base58Check payload length"));
+    }
+    let mut h = [0u8; 20];
+    h.copy_from_slice(&payload[1..]);
+    Ok((payload[0], h))
+}
+
+/// Decode a native SegWit v0 key-hash (P2WPKH, `bc1q…` on Bitcoin) address
+/// of `chain` into the HASH160 of the compressed public key it commits to.
+pub fn decode_p2wpkh_to_hash160(addr: &str, chain: &Chain) -> Result<[u8; 20]> {
+    let (hrp, version, program) =
+        bech32::segwit::decode(addr).map_err(|e| anyhow!("Invalid bech32 address: {e}"))?;
+    let expected = chain
+        .hrp
+        .ok_or_else(|| anyhow!("Chain has no SegWit addresses"))?;
+    if hrp != expected {
+        return Err(anyhow!(
+            "Not a SegWit address of this chain (HRP {hrp}, expected {expected})"
+        ));
+    }
+    if version != Fe32::Q {
+        return Err(anyhow!(
+            "Only witness version 0 is supported, got {}",
+            version.to_u8()
+        ));
+    }
+    <[u8; 20]>::try_from(program)
+        .map_err(|p| anyhow!("P2WPKH program must be 20 bytes, got {}", p.len()))
+}
+
+/// Decode a Taproot (`bc1p…` on Bitcoin) address of `chain` into the
+/// 32-byte x-only output key it pays to.
+pub fn decode_p2tr_to_output_key(addr: &str, chain: &Chain) -> Result<[u8; 32]> {
+    let (hrp, version, program) =
+        bech32::segwit::decode(addr).map_err(|e| anyhow!("Invalid bech32m address: {e}"))?;
+    let expected = chain
+        .hrp
+        .ok_or_else(|| anyhow!("Chain has no SegWit addresses"))?;
+    if hrp != expected {
+        return Err(anyhow!(
+            "Not a SegWit address of this chain (HRP {hrp}, expected {expected})"
+        ));
+    }
+    if version != Fe32::P {
+        return Err(anyhow!(
+            "Only witness version 1 is supported, got {}",
+            version.to_u8()
+        ));
+    }
+    <[u8; 32]>::try_from(program)
+        .map_err(|p| anyhow!("P2TR program must be 32 bytes, got {}", p.len()))
+}
+
+/// BIP341 output key for `internal_key` with no script tree:
+/// `P + H_TapTweak(P)·G`, x-only.
+pub fn taproot_output_key<C: Verification>(
+    secp: &Secp256k1<C>,
+    internal_key: &XOnlyPublicKey,
+) -> [u8; 32] {
+    let tag = Sha256::digest(b"TapTweak");
+    let tweak = Sha256::new()
+        .chain_update(tag)
+        .chain_update(tag)
+        .chain_update(internal_key.serialize())
+        .finalize();
+    let tweak = Scalar::from_be_bytes(tweak.into()).expect("tweak below the curve order");
+    let (output_key, _) = internal_key
+        .add_tweak(secp, &tweak)
+        .expect("tweaked key is not infinity");
+    output_key.serialize()
+}
+
+/// `RIPEMD160(SHA256(data))`.
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    let sha = Sha256::digest(data);
+    let mut rip = Ripemd160::new();
+    rip.update(sha);
+    let out = rip.finalize();
+    let mut h = [0u8; 20];
+    h.copy_from_slice(&out);
+    h
+}
+
+/// P2PKH address of `chain` for a compressed public key.
+pub fn p2pkh_from_pubkey_compressed(pk33: &[u8; 33], chain: &Chain) -> String {
+    p2pkh_from_hash160(&hash160(pk33), chain)
+}
+
+/// P2PKH address of `chain` committing to `h160`.
+pub fn p2pkh_from_hash160(h160: &[u8; 20], chain: &Chain) -> String {
+    let mut payload = Vec::with_capacity(1 + 20 + 4);
+    payload.push(chain.p2pkh_version);
+    payload.extend_from_slice(h160);
+    base58check(&payload)
+}
+
+/// P2SH (`3…` on Bitcoin) address of `chain` committing to the script
+/// hash `h160`.
+pub fn p2sh_from_hash160(h160: &[u8; 20], chain: &Chain) -> String {
+    let mut payload = Vec::with_capacity(1 + 20 + 4);
+    payload.push(chain.p2sh_version);
+    payload.extend_from_slice(h160);
+    base58check(&payload)
+}
+
+/// The P2WPKH witness program `OP_0 <20-byte key hash>`, which is also the
+/// redeem script of a nested SegWit (P2SH-P2WPKH) address.
+pub fn p2wpkh_script(h160: &[u8; 20]) -> [u8; 22] {
+    let mut script = [0u8; 22];
+    script[0] = 0x00;
+    script[1] = 0x14;
+    script[2..].copy_from_slice(h160);
+    script
+}
+
+fn segwit_hrp(chain: &Chain) -> bech32::Hrp {
+    chain.hrp.expect("chain has SegWit addresses")
+}
+
+/// P2WPKH (`bc1q…` on Bitcoin) address of `chain` committing to `h160`.
+///
+/// # Panics
+///
+/// If `chain` has no bech32 HRP.
+pub fn p2wpkh_from_hash160(h160: &[u8; 20], chain: &Chain) -> String {
+    bech32::segwit::encode_v0(segwit_hrp(chain), h160).expect("20-byte v0 program is valid")
+}
+
+/// Taproot (`bc1p…` on Bitcoin) address of `chain` paying to
+/// `output_key`.
+///
+/// # Panics
+///
+/// If `chain` has no bech32 HRP.
+pub fn p2tr_from_output_key(output_key: &[u8; 32], chain: &Chain) -> String {
+    bech32::segwit::encode_v1(segwit_hrp(chain), output_key).expect("32-byte v1 program is valid")
+}
+
+/// WIF encoding of a secret key for `chain`. `compressed` appends the
+/// `0x01` flag telling wallets to derive the compressed public key.
+pub fn wif_from_secret(sk: &SecretKey, compressed: bool, chain: &Chain) -> String {
+    let mut payload = Vec::with_capacity(1 + 32 + 1 + 4);
+    payload.push(chain.wif_prefix);
+    payload.extend_from_slice(&sk.secret_bytes());
+    if compressed {
+        payload.push(0x01);
+    }
+    base58check(&payload)
+}
+
+/// Decode an Ethereum `0x…` address. Mixed-case addresses must carry a
+/// valid EIP-55 checksum; all-lowercase and all-uppercase ones carry none.
+pub fn decode_eth_address(addr: &str) -> Result<[u8; 20]> {
+    let digits = addr
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow!("Ethereum address must start with 0x"))?;
+    let mut address = [0u8; 20];
+    hex::decode_to_slice(digits, &mut address)
+        .map_err(|_| anyhow!("Ethereum address must be 40 hex digits"))?;
+    let mixed_case = digits.bytes().any(|b| b.is_ascii_lowercase())
+        && digits.bytes().any(|b| b.is_ascii_uppercase());
+    if mixed_case && eth_checksum_encode(&address) != addr {
+        return Err(anyhow!("Invalid EIP-55 checksum"));
+    }
+    Ok(address)
+}
+
+/// Ethereum address of a 65-byte uncompressed public key: the last 20
+/// bytes of the Keccak-256 of its 64-byte `X || Y`.
+pub fn eth_address_from_pubkey(pk65: &[u8; 65]) -> [u8; 20] {
+    let hash = Keccak256::digest(&pk65[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// EIP-55 encoding of an Ethereum address: hex letters are uppercased where
+/// the matching nibble of the Keccak-256 of the lowercase hex is 8 or more.
+pub fn eth_checksum_encode(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = Keccak256::digest(lower.as_bytes());
+    let digits: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = hash[i / 2] >> (4 * (1 - i % 2)) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{digits}")
+}
+
+/// NIP-19 human-readable parts of Nostr public and secret keys.
+const NPUB: Hrp = Hrp::parse_unchecked("npub");
+const NSEC: Hrp = Hrp::parse_unchecked("nsec");
+
+/// Decode a NIP-19 `npub…` Nostr public key into its 32-byte x-only key.
+pub fn decode_npub(npub: &str) -> Result<[u8; 32]> {
+    let checked =
+        CheckedHrpstring::new::<Bech32>(npub).map_err(|e| anyhow!("Invalid bech32 npub: {e}"))?;
+    if checked.hrp() != NPUB {
+        return Err(anyhow!("Not an npub (HRP {})", checked.hrp()));
+    }
+    let key: Vec<u8> = checked.byte_iter().collect();
+    <[u8; 32]>::try_from(key).map_err(|k| anyhow!("npub key must be 32 bytes, got {}", k.len()))
+}
+
+/// NIP-19 `npub…` encoding of a 32-byte x-only public key.
+pub fn npub_from_xonly(key: &[u8; 32]) -> String {
+    bech32::encode::<Bech32>(NPUB, key).expect("32-byte key fits in bech32")
+}
+
+/// NIP-19 `nsec…` encoding of a secret key.
+pub fn nsec_from_secret(sk: &SecretKey) -> String {
+    bech32::encode::<Bech32>(NSEC, &sk.secret_bytes()).expect("32-byte key fits in bech32")
+}
+
+/// Append the 4-byte double-SHA256 checksum and Base58-encode.
+pub fn base58check(payload: &[u8]) -> String {
+    let c = Sha256::digest(Sha256::digest(payload));
+    let mut v = payload.to_vec();
+    v.extend_from_slice(&c[..4]);
+    bs58::encode(v).into_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+
+    #[test]
+    fn hash160_matches_known_vector() {
+        let h = hash160(b"hello");
+        assert_eq!(
+            h,
+            [
+                0xb6, 0xa9, 0xc8, 0xc2, 0x30, 0x72, 0x2b, 0x7c, 0x74, 0x83, 0x31, 0xa8, 0xb4, 0x50,
+                0xf0, 0x55, 0x66, 0xdc, 0x7d, 0x0f,
+            ]
+        );
+    }
+
+    #[test]
+    fn base58check_encodes_payload() {
+        let payload = [0u8; 21];
+        let s = base58check(&payload);
+        assert_eq!(s, "1111111111111111111114oLvT2");
+    }
+
+    #[test]
+    fn decode_p2pkh_to_hash160_known_address() {
+        let h =
+            decode_p2pkh_to_hash160("1CfZWK1QTQE3eS9qn61dQjV89KDjZzfNcv", &Chain::BITCOIN).unwrap();
+        assert_eq!(
+            h,
+            [
+                0x7f, 0xf4, 0x53, 0x03, 0x77, 0x4e, 0xf7, 0xa5, 0x2f, 0xff, 0xd8, 0x01, 0x19, 0x81,
+                0x03, 0x4b, 0x25, 0x8c, 0xb8, 0x6b,
+            ]
+        );
+    }
+
+    #[test]
+    fn p2wpkh_round_trips_bip173_vector() {
+        // BIP173: P2WPKH of 0279be66...f81798.
+        let addr = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let h = decode_p2wpkh_to_hash160(addr, &Chain::BITCOIN).unwrap();
+        assert_eq!(hex::encode(h), "751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(p2wpkh_from_hash160(&h, &Chain::BITCOIN), addr);
+        // Upper case is valid bech32.
+        assert_eq!(
+            decode_p2wpkh_to_hash160(&addr.to_uppercase(), &Chain::BITCOIN).unwrap(),
+            h
+        );
+    }
+
+    #[test]
+    fn decode_p2wpkh_rejects_other_programs() {
+        // Bad checksum.
+        assert!(
+            decode_p2wpkh_to_hash160(
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+                &Chain::BITCOIN
+            )
+            .is_err()
+        );
+        // Testnet.
+        assert!(
+            decode_p2wpkh_to_hash160(
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+                &Chain::BITCOIN
+            )
+            .is_err()
+        );
+        // P2WSH: version 0 with a 32-byte program.
+        let err = decode_p2wpkh_to_hash160(
+            "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+            &Chain::BITCOIN,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("20 bytes"), "{err}");
+        // Taproot: version 1.
+        let err = decode_p2wpkh_to_hash160(
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+            &Chain::BITCOIN,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("version 0"), "{err}");
+    }
+
+    #[test]
+    fn p2sh_p2wpkh_of_generator_key() {
+        let key_hash = decode_p2wpkh_to_hash160(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            &Chain::BITCOIN,
+        )
+        .unwrap();
+        let script = p2wpkh_script(&key_hash);
+        assert_eq!(
+            hex::encode(script),
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+        let addr = "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN";
+        assert_eq!(
+            decode_p2sh_to_hash160(addr, &Chain::BITCOIN).unwrap(),
+            hash160(&script)
+        );
+        assert_eq!(p2sh_from_hash160(&hash160(&script), &Chain::BITCOIN), addr);
+        // Each decoder only takes its own version byte.
+        assert!(
+            decode_p2sh_to_hash160("1CfZWK1QTQE3eS9qn61dQjV89KDjZzfNcv", &Chain::BITCOIN).is_err()
+        );
+        assert!(decode_p2pkh_to_hash160(addr, &Chain::BITCOIN).is_err());
+    }
+
+    #[test]
+    fn taproot_output_keys_match_bip86_and_bip341_vectors() {
+        let secp = Secp256k1::verification_only();
+        for (internal, output, addr) in [
+            // BIP86: m/86'/0'/0'/0/0 of the "abandon … about" mnemonic.
+            (
+                "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+                "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+            // BIP341 wallet vector with no script tree.
+            (
+                "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+                "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+                "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5",
+            ),
+        ] {
+            let internal = XOnlyPublicKey::from_slice(&hex::decode(internal).unwrap()).unwrap();
+            let key = taproot_output_key(&secp, &internal);
+            assert_eq!(hex::encode(key), output);
+            assert_eq!(p2tr_from_output_key(&key, &Chain::BITCOIN), addr);
+            assert_eq!(
+                decode_p2tr_to_output_key(addr, &Chain::BITCOIN).unwrap(),
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn decode_p2tr_rejects_other_programs() {
+        // BIP350: bech32 (not bech32m) checksum on a version 1 program.
+        assert!(
+            decode_p2tr_to_output_key(
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+                &Chain::BITCOIN
+            )
+            .is_err()
+        );
+        let err = decode_p2tr_to_output_key(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            &Chain::BITCOIN,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("version 1"), "{err}");
+    }
+
+    #[test]
+    fn addresses_and_wif_use_network_prefixes() {
+        let h = decode_p2wpkh_to_hash160(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            &Chain::BITCOIN,
+        )
+        .unwrap();
+        let script_hash = hash160(&p2wpkh_script(&h));
+        let mut b = [0u8; 32];
+        b[31] = 1;
+        let sk = SecretKey::from_slice(&b).unwrap();
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            let chain = &network.chain();
+            let p2pkh = p2pkh_from_hash160(&h, chain);
+            assert_eq!(p2pkh, "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r");
+            assert_eq!(decode_p2pkh_to_hash160(&p2pkh, chain).unwrap(), h);
+            let p2sh = p2sh_from_hash160(&script_hash, chain);
+            assert_eq!(p2sh, "2NAUYAHhujozruyzpsFRP63mbrdaU5wnEpN");
+            assert_eq!(decode_p2sh_to_hash160(&p2sh, chain).unwrap(), script_hash);
+            assert_eq!(
+                wif_from_secret(&sk, true, chain),
+                "cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN87JcbXMTcA"
+            );
+            assert!(decode_p2pkh_to_hash160(&p2pkh, &Chain::BITCOIN).is_err());
+        }
+        assert_eq!(
+            p2wpkh_from_hash160(&h, &Network::Signet.chain()),
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+        );
+        let regtest = p2wpkh_from_hash160(&h, &Network::Regtest.chain());
+        assert_eq!(regtest, "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080");
+        let err = decode_p2wpkh_to_hash160(&regtest, &Network::Testnet.chain()).unwrap_err();
+        assert!(err.to_string().contains("expected tb"), "{err}");
+    }
+
+    #[test]
+    fn addresses_and_wif_use_chain_prefixes() {
+        let h =
+            decode_p2pkh_to_hash160("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", &Chain::BITCOIN).unwrap();
+        let mut b = [0u8; 32];
+        b[31] = 1;
+        let sk = SecretKey::from_slice(&b).unwrap();
+        let chains = crate::chain::Chains::default();
+        for (name, addr, wif) in [
+            (
+                "litecoin",
+                "LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ",
+                "T33ydQRKp4FCW5LCLLUB7deioUMoveiwekdwUwyfRDeGZm76aUjV",
+            ),
+            (
+                "dogecoin",
+                "DFpN6QqFfUm3gKNaxN6tNcab1FArL9cZLE",
+                "QNcdLVw8fHkixm6NNyN6nVwxKek4u7qrioRbQmjxac5TVoTtZuot",
+            ),
+            (
+                "dash",
+                "XmN7PQYWKn5MJFna5fRYgP6mxT2F7xpekE",
+                "XBHddvWWiMu3nZhhpTXBQWJMmdz5JNKJD85b9fgKAckCT2coW3Y4",
+            ),
+        ] {
+            let chain = &chains.get(name).unwrap();
+            assert_eq!(p2pkh_from_hash160(&h, chain), addr);
+            assert_eq!(decode_p2pkh_to_hash160(addr, chain).unwrap(), h);
+            assert_eq!(wif_from_secret(&sk, true, chain), wif);
+        }
+        let err = decode_p2wpkh_to_hash160(
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            &chains.get("dogecoin").unwrap(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("no SegWit"), "{err}");
+    }
+
+    #[test]
+    fn eth_addresses_round_trip_eip55_vectors() {
+        for addr in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+        ] {
+            let address = decode_eth_address(addr).unwrap();
+            assert_eq!(eth_checksum_encode(&address), addr);
+            let lower = addr.to_ascii_lowercase();
+            assert_eq!(decode_eth_address(&lower).unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn decode_eth_address_rejects_bad_checksums_and_lengths() {
+        let err = decode_eth_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{err}");
+        assert!(decode_eth_address("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(decode_eth_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea").is_err());
+        assert!(decode_eth_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beagg").is_err());
+    }
+
+    #[test]
+    fn eth_address_of_generator_key() {
+        let secp = Secp256k1::new();
+        let mut b = [0u8; 32];
+        b[31] = 1;
+        let sk = SecretKey::from_slice(&b).unwrap();
+        let pk = secp256k1::PublicKey::from_secret_key(&secp, &sk).serialize_uncompressed();
+        assert_eq!(
+            eth_checksum_encode(&eth_address_from_pubkey(&pk)),
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+        );
+    }
+
+    #[test]
+    fn nostr_keys_round_trip_nip19_vectors() {
+        let npub = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+        let key = decode_npub(npub).unwrap();
+        assert_eq!(
+            hex::encode(key),
+            "7e7e9c42a91bfef19fa929e5fda1b72e0ebc1a4c1141673e2794234d86addf4e"
+        );
+        assert_eq!(npub_from_xonly(&key), npub);
+
+        let sk = SecretKey::from_slice(
+            &hex::decode("67dea2ed018072d675f5415ecfaed7d2597555e202d85b3d65ea4e58d2d92ffa")
+                .unwrap(),
+        )
+        .unwrap();
+        let nsec = nsec_from_secret(&sk);
+        assert_eq!(
+            nsec,
+            "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5"
+        );
+        let err = decode_npub(&nsec).unwrap_err();
+        assert!(err.to_string().contains("Not an npub"), "{err}");
+        // Taproot addresses are bech32m, which NIP-19 does not use.
+        assert!(
+            decode_npub("bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9").is_err()
+        );
+    }
+
+    #[test]
+    fn p2pkh_from_pubkey_compressed_known() {
+        let pk_bytes =
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap();
+        let mut pk = [0u8; 33];
+        pk.copy_from_slice(&pk_bytes);
+        let addr = p2pkh_from_pubkey_compressed(&pk, &Chain::BITCOIN);
+        assert_eq!(addr, "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+    }
+
+    #[test]
+    fn wif_from_secret_known() {
+        let mut b = [0u8; 32];
+        b[31] = 1;
+        let sk = SecretKey::from_slice(&b).unwrap();
+        let wif = wif_from_secret(&sk, true, &Chain::BITCOIN);
+        assert_eq!(wif, "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn");
+        let wif = wif_from_secret(&sk, false, &Chain::BITCOIN);
+        assert_eq!(wif, "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf");
+    }
+}
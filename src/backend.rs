@@ -0,0 +1,67 @@
+//! The interface between the search loop and whatever generates candidates.
+
+use crate::u256::U256;
+use anyhow::Result;
+
+/// Number of result slots every backend provides. Two is enough for the
+/// search loop to verify one batch while the next is being generated.
+pub const SLOTS: usize = 2;
+
+/// Layout of the records returned by [`CandidateBackend::results`]. Except
+/// for [`BatchFormat::HitIndices`], record `i` belongs to private key
+/// `start + i` of the submitted batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchFormat {
+    /// 32-byte little-endian private keys, as written by `shaders/seq.wgsl`.
+    Keys,
+    /// 33-byte compressed SEC1 public keys, as written by
+    /// `shaders/pubkey.wgsl`. Keys outside `1..n` yield an all-zero record.
+    CompressedPubKeys,
+    /// 20-byte HASH160 of each compressed public key, as written by
+    /// `shaders/pubkey.wgsl` in hash mode. Keys outside `1..n` yield an
+    /// all-zero record.
+    Hash160,
+    /// Little-endian `u32` in-batch indices of keys whose HASH160 matched the
+    /// backend's target, in no particular order. A batch usually has none; a
+    /// backend that cannot hold every match returns every index instead.
+    HitIndices,
+}
+
+impl BatchFormat {
+    /// Bytes per record.
+    pub fn record_len(self) -> usize {
+        match self {
+            BatchFormat::Keys => 32,
+            BatchFormat::CompressedPubKeys => 33,
+            BatchFormat::Hash160 => 20,
+            BatchFormat::HitIndices => 4,
+        }
+    }
+}
+
+/// A device that turns key ranges into batches of candidates.
+///
+/// A batch is submitted into one of [`SLOTS`] slots, waited on, and then
+/// borrowed as `n` records laid out per [`CandidateBackend::format`]. A slot
+/// is only resubmitted after its previous batch has been waited on and its
+/// results dropped.
+pub trait CandidateBackend {
+    /// Layout of every batch this backend produces.
+    fn format(&self) -> BatchFormat {
+        BatchFormat::Keys
+    }
+
+    /// Largest `n` accepted by [`CandidateBackend::submit`].
+    fn max_batch(&self) -> u32 {
+        u32::MAX
+    }
+
+    /// Start generating `n` keys beginning at `start` into `slot`.
+    fn submit(&mut self, slot: usize, start: U256, n: u32) -> Result<()>;
+
+    /// Block until the batch in `slot` is complete and its results readable.
+    fn wait(&mut self, slot: usize) -> Result<()>;
+
+    /// The completed batch in `slot`.
+    fn results(&self, slot: usize) -> &[u8];
+}
@@ -0,0 +1,261 @@
+//! Address and key prefixes of secp256k1 chains: the Bitcoin networks, a
+//! built-in table of forks, and custom chains read from a TOML file.
+//!
+//! A chains file has one table per chain; `hrp` may be left out for chains
+//! without SegWit addresses:
+//!
+//! ```toml
+//! [litecoin-testnet]
+//! p2pkh_version = 0x6f
+//! p2sh_version = 0x3a
+//! wif_prefix = 0xef
+//! hrp = "tltc"
+//! ```
+
+use anyhow::{Context, Result, anyhow};
+use bech32::{Hrp, hrp};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{DocumentMut, Item};
+
+/// The version bytes and bech32 HRP a chain's addresses and keys use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Chain {
+    /// Base58Check version byte of P2PKH addresses.
+    pub p2pkh_version: u8,
+    /// Base58Check version byte of P2SH addresses.
+    pub p2sh_version: u8,
+    /// Version byte of WIF private keys.
+    pub wif_prefix: u8,
+    /// Human-readable part of SegWit addresses; `None` if the chain has
+    /// none.
+    pub hrp: Option<Hrp>,
+}
+
+impl Chain {
+    /// Bitcoin mainnet.
+    pub const BITCOIN: Self = Self {
+        p2pkh_version: 0x00,
+        p2sh_version: 0x05,
+        wif_prefix: 0x80,
+        hrp: Some(hrp::BC),
+    };
+
+    /// Bitcoin testnet and signet.
+    pub const BITCOIN_TESTNET: Self = Self {
+        p2pkh_version: 0x6f,
+        p2sh_version: 0xc4,
+        wif_prefix: 0xef,
+        hrp: Some(hrp::TB),
+    };
+
+    /// Bitcoin regtest: testnet's Base58 prefixes with its own HRP.
+    pub const BITCOIN_REGTEST: Self = Self {
+        hrp: Some(hrp::BCRT),
+        ..Self::BITCOIN_TESTNET
+    };
+
+    /// A chain with the given prefixes and no SegWit addresses.
+    const fn base58(p2pkh_version: u8, p2sh_version: u8, wif_prefix: u8) -> Self {
+        Self {
+            p2pkh_version,
+            p2sh_version,
+            wif_prefix,
+            hrp: None,
+        }
+    }
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self::BITCOIN
+    }
+}
+
+/// Chains known by name: the built-in table, then any custom chains.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chains {
+    chains: BTreeMap<String, Chain>,
+}
+
+impl Chains {
+    /// Names of the built-in chains, as `--chain` accepts them.
+    pub const BUILTIN: [&str; 6] = [
+        "bitcoin", "litecoin", "dogecoin", "dash", "namecoin", "digibyte",
+    ];
+
+    /// The built-in chains plus those defined in the chains file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("cannot read chains file {}", path.display()))?;
+        text.parse()
+            .with_context(|| format!("invalid chains file {}", path.display()))
+    }
+
+    /// The chain called `name`.
+    pub fn get(&self, name: &str) -> Result<Chain> {
+        self.chains.get(name).copied().ok_or_else(|| {
+            let known: Vec<_> = self.chains.keys().map(String::as_str).collect();
+            anyhow!("unknown chain {name:?}; known chains: {}", known.join(", "))
+        })
+    }
+
+    /// Define `name`, replacing any chain already called that.
+    pub fn insert(&mut self, name: impl Into<String>, chain: Chain) {
+        self.chains.insert(name.into(), chain);
+    }
+}
+
+impl Default for Chains {
+    /// The built-in chains.
+    fn default() -> Self {
+        let hrp = |s| Some(Hrp::parse_unchecked(s));
+        let table = [
+            Chain::BITCOIN,
+            Chain {
+                hrp: hrp("ltc"),
+                ..Chain::base58(0x30, 0x32, 0xb0)
+            },
+            Chain::base58(0x1e, 0x16, 0x9e),
+            Chain::base58(0x4c, 0x10, 0xcc),
+            Chain {
+                hrp: hrp("nc"),
+                ..Chain::base58(0x34, 0x0d, 0xb4)
+            },
+            Chain {
+                hrp: hrp("dgb"),
+                ..Chain::base58(0x1e, 0x3f, 0x80)
+            },
+        ];
+        Self {
+            chains: Self::BUILTIN
+                .into_iter()
+                .map(String::from)
+                .zip(table)
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for Chains {
+    type Err = anyhow::Error;
+
+    /// The built-in chains plus those defined in the TOML text `s`; a custom
+    /// chain replaces a built-in one of the same name.
+    fn from_str(s: &str) -> Result<Self> {
+        let doc: DocumentMut = s.parse()?;
+        let mut chains = Self::default();
+        for (name, item) in doc.iter() {
+            let chain = parse_chain(item).with_context(|| format!("chain {name:?}"))?;
+            chains.insert(name, chain);
+        }
+        Ok(chains)
+    }
+}
+
+fn parse_chain(item: &Item) -> Result<Chain> {
+    let table = item
+        .as_table_like()
+        .ok_or_else(|| anyhow!("expected a table"))?;
+    let byte = |key: &str| -> Result<u8> {
+        let value = table
+            .get(key)
+            .ok_or_else(|| anyhow!("missing {key}"))?
+            .as_integer()
+            .ok_or_else(|| anyhow!("{key} must be an integer"))?;
+        u8::try_from(value).map_err(|_| anyhow!("{key} {value} does not fit in a byte"))
+    };
+    let hrp = match table.get("hrp") {
+        None => None,
+        Some(item) => {
+            let s = item
+                .as_str()
+                .ok_or_else(|| anyhow!("hrp must be a string"))?;
+            Some(Hrp::parse(s).with_context(|| format!("invalid hrp {s:?}"))?)
+        }
+    };
+    if let Some((key, _)) = table
+        .iter()
+        .find(|(key, _)| !["p2pkh_version", "p2sh_version", "wif_prefix", "hrp"].contains(key))
+    {
+        return Err(anyhow!("unknown key {key:?}"));
+    }
+    Ok(Chain {
+        p2pkh_version: byte("p2pkh_version")?,
+        p2sh_version: byte("p2sh_version")?,
+        wif_prefix: byte("wif_prefix")?,
+        hrp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_table_names_every_chain() {
+        let chains = Chains::default();
+        for name in Chains::BUILTIN {
+            assert!(chains.get(name).is_ok(), "{name}");
+        }
+        assert_eq!(chains.get("bitcoin").unwrap(), Chain::BITCOIN);
+        let litecoin = chains.get("litecoin").unwrap();
+        assert_eq!(litecoin.wif_prefix, 0xb0);
+        assert_eq!(litecoin.hrp.unwrap().to_string(), "ltc");
+        assert_eq!(chains.get("dogecoin").unwrap().hrp, None);
+        let err = chains.get("bitcoin-sv").unwrap_err();
+        assert!(
+            err.to_string().contains("known chains: bitcoin, dash"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn parses_custom_chains_from_toml() {
+        let chains: Chains = "
+            # Litecoin's test network
+            [litecoin-testnet]
+            p2pkh_version = 0x6f
+            p2sh_version = 0x3a
+            wif_prefix = 0xef
+            hrp = \"tltc\"
+
+            [dash]
+            p2pkh_version = 140
+            p2sh_version = 19
+            wif_prefix = 239
+        "
+        .parse()
+        .unwrap();
+        let testnet = chains.get("litecoin-testnet").unwrap();
+        assert_eq!(testnet.p2sh_version, 0x3a);
+        assert_eq!(testnet.hrp.unwrap().to_string(), "tltc");
+        assert_eq!(chains.get("dash").unwrap(), Chain::base58(140, 19, 239));
+        assert_eq!(chains.get("litecoin").unwrap().p2pkh_version, 0x30);
+    }
+
+    #[test]
+    fn rejects_malformed_chains() {
+        let chain = |body: &str| format!("[mycoin]\n{body}").parse::<Chains>().unwrap_err();
+        let full = "p2pkh_version = 0\np2sh_version = 5\nwif_prefix = 128\n";
+        for (body, expected) in [
+            (
+                "p2pkh_version = 0\np2sh_version = 5\n",
+                "missing wif_prefix",
+            ),
+            (&full.replace("128", "256"), "does not fit in a byte"),
+            (&full.replace("128", "\"80\""), "must be an integer"),
+            (&format!("{full}hrp = 1\n"), "hrp must be a string"),
+            (&format!("{full}hrp = \"b c\"\n"), "invalid hrp"),
+            (&format!("{full}bip44 = 0\n"), "unknown key \"bip44\""),
+        ] {
+            let err = chain(body);
+            assert!(format!("{err:#}").contains(expected), "{err:#}");
+            assert!(format!("{err:#}").contains("chain \"mycoin\""), "{err:#}");
+        }
+        assert!("mycoin = 1".parse::<Chains>().is_err());
+        assert!("[mycoin".parse::<Chains>().is_err());
+    }
+}
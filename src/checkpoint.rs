@@ -0,0 +1,324 @@
+//! Checkpoint files: the progress of a search, saved so a long run can be
+//! resumed after the process dies.
+//!
+//! The file is plain `key = value` text:
+//!
+//! ```text
+//! # gpu-bitcrack checkpoint
+//! version = 4
+//! keyspace = 1000000:1ffffff
+//! targets = 6f2c0a34c1e0b8a3e7e4e9a2f6b1d0c5a4e3f2d1c0b9a8f7e6d5c4b3a2918070
+//! pubkeys = compressed
+//! batch = 1000000
+//! last_verified = 1a7ffff
+//! found = 12b4c3d 1PitScNLyp2HCygzadCh7FveTnfmpPbfp8
+//! ```
+//!
+//! Every key from the start of `keyspace` through `last_verified` has been
+//! checked against the targets, which are identified by
+//! [`TargetSet::digest`](crate::TargetSet::digest), in the public key forms named by `pubkeys`. Each
+//! `found` line is a hit in that prefix: its private key and the target it
+//! matched. Numbers are hex, as on the command line.
+
+use crate::search::{Keyspace, PubkeyFormat};
+use crate::u256::U256;
+use anyhow::{Context, Result, anyhow};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Format version written by this build; later versions are rejected.
+pub const VERSION: u32 = 4;
+
+/// Saved progress of a search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub keyspace: Keyspace,
+    /// [`TargetSet::digest`](crate::TargetSet::digest) of the targets searched for.
+    pub targets_digest: [u8; 32],
+    pub pubkeys: PubkeyFormat,
+    pub batch: u32,
+    /// Last key of the verified prefix of `keyspace`.
+    pub last_verified: U256,
+    /// Private key and target of each hit in the verified prefix, in the
+    /// order they were found.
+    pub found: Vec<(U256, String)>,
+}
+
+impl Checkpoint {
+    /// Read and parse the checkpoint at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("cannot read checkpoint {}", path.display()))?;
+        text.parse()
+            .with_context(|| format!("invalid checkpoint {}", path.display()))
+    }
+
+    /// Write to `path` atomically: a sibling temporary file is written,
+    /// synced and renamed over `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let write = || -> std::io::Result<()> {
+            let mut file = fs::File::create(&tmp)?;
+            file.write_all(self.to_string().as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&tmp, path)
+        };
+        write().with_context(|| format!("cannot write checkpoint {}", path.display()))
+    }
+
+    /// Fail unless this checkpoint was saved by a search of `keyspace` for
+    /// the targets with `targets_digest` as `pubkeys` in batches of `batch`.
+    pub fn ensure_matches(
+        &self,
+        keyspace: &Keyspace,
+        targets_digest: &[u8; 32],
+        pubkeys: PubkeyFormat,
+        batch: u32,
+    ) -> Result<()> {
+        if self.keyspace != *keyspace {
+            return Err(anyhow!(
+                "checkpoint keyspace {} does not match {keyspace}",
+                self.keyspace
+            ));
+        }
+        if self.targets_digest != *targets_digest {
+            return Err(anyhow!(
+                "checkpoint target set {} does not match {}",
+                hex::encode(self.targets_digest),
+                hex::encode(targets_digest)
+            ));
+        }
+        if self.pubkeys != pubkeys {
+            return Err(anyhow!(
+                "checkpoint public key format {} does not match {pubkeys}",
+                self.pubkeys
+            ));
+        }
+        if self.batch != batch {
+            return Err(anyhow!(
+                "checkpoint batch {} does not match {batch}",
+                self.batch
+            ));
+        }
+        Ok(())
+    }
+
+    /// Keys still to search, or `None` once the whole keyspace is verified.
+    pub fn remaining(&self) -> Option<Keyspace> {
+        if self.last_verified >= self.keyspace.end() {
+            return None;
+        }
+        Keyspace::new(self.last_verified + 1, self.keyspace.end()).ok()
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# gpu-bitcrack checkpoint")?;
+        writeln!(f, "version = {VERSION}")?;
+        writeln!(f, "keyspace = {}", self.keyspace)?;
+        writeln!(f, "targets = {}", hex::encode(self.targets_digest))?;
+        writeln!(f, "pubkeys = {}", self.pubkeys)?;
+        writeln!(f, "batch = {}", self.batch)?;
+        writeln!(f, "last_verified = {:x}", self.last_verified)?;
+        for (key, target) in &self.found {
+            writeln!(f, "found = {key:x} {target}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut version = None;
+        let mut keyspace = None;
+        let mut targets = None;
+        let mut pubkeys = None;
+        let mut batch = None;
+        let mut last_verified = None;
+        let mut found = Vec::new();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected `key = value`, got {line:?}"))?;
+            let value = value.trim();
+            match key.trim() {
+                "version" => version = Some(value.parse::<u32>()?),
+                "keyspace" => keyspace = Some(value.parse::<Keyspace>()?),
+                "targets" => {
+                    let bytes = hex::decode(value)?;
+                    targets = Some(
+                        <[u8; 32]>::try_from(bytes)
+                            .map_err(|_| anyhow!("targets must be a 32-byte digest"))?,
+                    );
+                }
+                "pubkeys" => pubkeys = Some(value.parse()?),
+                "batch" => batch = Some(value.parse::<u32>()?),
+                "last_verified" => last_verified = Some(U256::from_hex(value)?),
+                "found" => {
+                    let (key, target) = value
+                        .split_once(' ')
+                        .ok_or_else(|| anyhow!("expected `found = <key> <target>`"))?;
+                    found.push((U256::from_hex(key)?, target.trim().to_owned()));
+                }
+                other => return Err(anyhow!("unknown key {other:?}")),
+            }
+        }
+
+        match version {
+            Some(VERSION) => {}
+            Some(v) => return Err(anyhow!("unsupported checkpoint version {v}")),
+            None => return Err(anyhow!("missing version")),
+        }
+        let keyspace = keyspace.ok_or_else(|| anyhow!("missing keyspace"))?;
+        let last_verified = last_verified.ok_or_else(|| anyhow!("missing last_verified"))?;
+        if last_verified < keyspace.start() || last_verified > keyspace.end() {
+            return Err(anyhow!(
+                "last_verified {last_verified:x} is outside {keyspace}"
+            ));
+        }
+        Ok(Self {
+            keyspace,
+            targets_digest: targets.ok_or_else(|| anyhow!("missing targets"))?,
+            pubkeys: pubkeys.ok_or_else(|| anyhow!("missing pubkeys"))?,
+            batch: batch.ok_or_else(|| anyhow!("missing batch"))?,
+            last_verified,
+            found,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Checkpoint {
+        Checkpoint {
+            keyspace: "1000000:1ffffff".parse().unwrap(),
+            targets_digest: [0xab; 32],
+            pubkeys: PubkeyFormat::Both,
+            batch: 1_000_000,
+            last_verified: U256::from_u64(0x1a7_ffff),
+            found: vec![
+                (
+                    U256::from_u64(0x12b_4c3d),
+                    "1PitScNLyp2HCygzadCh7FveTnfmpPbfp8".into(),
+                ),
+                (
+                    U256::from_u64(0x1a0_0001),
+                    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let cp = sample();
+        let text = cp.to_string();
+        assert!(text.contains("version = 4\n"));
+        assert!(text.contains("keyspace = 1000000:1ffffff\n"));
+        assert!(text.contains("pubkeys = both\n"));
+        assert!(text.contains("last_verified = 1a7ffff\n"));
+        assert!(text.contains("found = 12b4c3d 1PitScNLyp2HCygzadCh7FveTnfmpPbfp8\n"));
+        assert_eq!(text.parse::<Checkpoint>().unwrap(), cp);
+
+        let none = Checkpoint {
+            found: Vec::new(),
+            ..cp
+        };
+        assert!(!none.to_string().contains("found"));
+        assert_eq!(none.to_string().parse::<Checkpoint>().unwrap(), none);
+    }
+
+    #[test]
+    fn rejects_other_versions_and_missing_fields() {
+        let text = sample().to_string();
+        let err = text
+            .replace("version = 4", "version = 5")
+            .parse::<Checkpoint>()
+            .unwrap_err();
+        assert!(err.to_string().contains("version 5"));
+        let err = text
+            .replace("version = 4", "version = 3")
+            .parse::<Checkpoint>()
+            .unwrap_err();
+        assert!(err.to_string().contains("version 3"), "{err}");
+        let no_target = text.replace(" 1PitScNLyp2HCygzadCh7FveTnfmpPbfp8", "");
+        assert!(no_target.parse::<Checkpoint>().is_err());
+        let no_pubkeys = text.replace("pubkeys = both\n", "");
+        assert!(no_pubkeys.parse::<Checkpoint>().is_err());
+        let no_batch: String = text
+            .lines()
+            .filter(|l| !l.starts_with("batch"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(no_batch.parse::<Checkpoint>().is_err());
+        let outside = text.replace("last_verified = 1a7ffff", "last_verified = 2000000");
+        assert!(outside.parse::<Checkpoint>().is_err());
+    }
+
+    #[test]
+    fn ensure_matches_names_the_mismatch() {
+        let cp = sample();
+        assert!(
+            cp.ensure_matches(&cp.keyspace, &[0xab; 32], PubkeyFormat::Both, 1_000_000)
+                .is_ok()
+        );
+        let other: Keyspace = "1000000:2ffffff".parse().unwrap();
+        let err = cp
+            .ensure_matches(&other, &[0xab; 32], PubkeyFormat::Both, 1_000_000)
+            .unwrap_err();
+        assert!(err.to_string().contains("keyspace"));
+        let err = cp
+            .ensure_matches(&cp.keyspace, &[0; 32], PubkeyFormat::Both, 1_000_000)
+            .unwrap_err();
+        assert!(err.to_string().contains("target set"));
+        let err = cp
+            .ensure_matches(
+                &cp.keyspace,
+                &[0xab; 32],
+                PubkeyFormat::Compressed,
+                1_000_000,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("public key format"));
+        let err = cp
+            .ensure_matches(&cp.keyspace, &[0xab; 32], PubkeyFormat::Both, 5)
+            .unwrap_err();
+        assert!(err.to_string().contains("batch"));
+    }
+
+    #[test]
+    fn remaining_starts_after_last_verified() {
+        let mut cp = sample();
+        let rest = cp.remaining().unwrap();
+        assert_eq!(rest.start(), U256::from_u64(0x1a8_0000));
+        assert_eq!(rest.end(), cp.keyspace.end());
+        cp.last_verified = cp.keyspace.end();
+        assert_eq!(cp.remaining(), None);
+    }
+
+    #[test]
+    fn save_replaces_file_atomically() {
+        let path = std::env::temp_dir().join(format!("gpu-bitcrack-cp-{}", std::process::id()));
+        let mut cp = sample();
+        cp.save(&path).unwrap();
+        cp.last_verified += 1;
+        cp.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), cp);
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        assert!(!Path::new(&tmp).exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+//! CPU sequence writer: the rayon counterpart of [`crate::gpu::GpuSeq`] for
+//! machines without a usable GPU adapter.
+
+use crate::backend::{CandidateBackend, SLOTS};
+use crate::u256::U256;
+use anyhow::Result;
+use rayon::prelude::*;
+
+/// Writes `start + idx` for each candidate as 32 little-endian bytes, the
+/// same layout `shaders/seq.wgsl` produces.
+///
+/// Batches are generated synchronously in [`CandidateBackend::submit`];
+/// [`CandidateBackend::wait`] has nothing left to do.
+#[derive(Clone, Debug, Default)]
+pub struct CpuSeq {
+    out: [Vec<u8>; SLOTS],
+}
+
+impl CpuSeq {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fill `out` with `n` keys starting at `start`, resizing it to `n * 32` bytes.
+    pub fn generate(start: U256, n: u32, out: &mut Vec<u8>) {
+        out.resize(n as usize * 32, 0);
+        out.par_chunks_exact_mut(32)
+            .enumerate()
+            .for_each(|(i, le32)| {
+                let key = start.wrapping_add(U256::from_u64(i as u64));
+                le32.copy_from_slice(&key.to_le_bytes());
+            });
+    }
+}
+
+impl CandidateBackend for CpuSeq {
+    fn submit(&mut self, slot: usize, start: U256, n: u32) -> Result<()> {
+        Self::generate(start, n, &mut self.out[slot]);
+        Ok(())
+    }
+
+    fn wait(&mut self, _slot: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn results(&self, slot: usize) -> &[u8] {
+        &self.out[slot]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_matches_gpu_layout() {
+        let start = U256::from_le_words([u32::MAX - 1, 0, 0, 0, 0, 0, 0, 0]);
+        let mut out = Vec::new();
+        CpuSeq::generate(start, 3, &mut out);
+        assert_eq!(out.len(), 96);
+        let words: Vec<u32> = out
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(&words[0..2], &[u32::MAX - 1, 0]);
+        assert_eq!(&words[8..10], &[u32::MAX, 0]);
+        assert_eq!(&words[16..18], &[0, 1]);
+    }
+
+    #[test]
+    fn slots_are_independent() {
+        let mut cpu = CpuSeq::new();
+        cpu.submit(0, U256::from_u64(5), 2).unwrap();
+        cpu.submit(1, U256::from_u64(9), 1).unwrap();
+        cpu.wait(0).unwrap();
+        cpu.wait(1).unwrap();
+        assert_eq!(cpu.results(0).len(), 64);
+        assert_eq!(cpu.results(0)[32], 6);
+        assert_eq!(cpu.results(1), &U256::from_u64(9).to_le_bytes());
+    }
+}
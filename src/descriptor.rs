@@ -0,0 +1,112 @@
+//! BIP380 output descriptor checksums: the eight characters after a `#`
+//! that catch typos in a descriptor.
+
+use anyhow::{Result, anyhow};
+
+/// Characters a descriptor may contain, in the order the checksum groups
+/// them.
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}\
+    IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// The bech32 alphabet the checksum is written in.
+const CHECKSUM_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The checksum of `desc`, which must not carry one already.
+pub fn checksum(desc: &str) -> Result<String> {
+    let mut c = 1;
+    let mut classes = 0;
+    let mut count = 0;
+    for ch in desc.chars() {
+        let pos = INPUT_CHARSET
+            .find(ch)
+            .ok_or_else(|| anyhow!("invalid character {ch:?} in descriptor"))?
+            as u64;
+        c = polymod(c, pos & 31);
+        classes = classes * 3 + (pos >> 5);
+        count += 1;
+        if count == 3 {
+            c = polymod(c, classes);
+            classes = 0;
+            count = 0;
+        }
+    }
+    if count > 0 {
+        c = polymod(c, classes);
+    }
+    for _ in 0..8 {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+    Ok((0..8)
+        .map(|i| CHECKSUM_CHARSET[((c >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect())
+}
+
+/// `desc` followed by `#` and its checksum.
+pub fn with_checksum(desc: &str) -> Result<String> {
+    Ok(format!("{desc}#{}", checksum(desc)?))
+}
+
+/// The descriptor `s` without its checksum, checking the checksum if there
+/// is one.
+pub fn strip_checksum(s: &str) -> Result<&str> {
+    let Some((desc, sum)) = s.split_once('#') else {
+        checksum(s)?;
+        return Ok(s);
+    };
+    let expected = checksum(desc)?;
+    if sum != expected {
+        return Err(anyhow!(
+            "descriptor checksum mismatch (got {sum:?}, expected {expected:?})"
+        ));
+    }
+    Ok(desc)
+}
+
+/// One step of the checksum's BCH code over GF(32).
+fn polymod(c: u64, val: u64) -> u64 {
+    const GENERATORS: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+    let c0 = c >> 35;
+    let mut c = ((c & 0x7ffffffff) << 5) ^ val;
+    for (i, g) in GENERATORS.iter().enumerate() {
+        if c0 >> i & 1 == 1 {
+            c ^= g;
+        }
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums_match_bitcoin_core() {
+        for (desc, sum) in [
+            ("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)", "02wpgw69"),
+            (
+                "pkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)",
+                "8fhd9pwu",
+            ),
+        ] {
+            assert_eq!(checksum(desc).unwrap(), sum, "{desc}");
+            assert_eq!(strip_checksum(&format!("{desc}#{sum}")).unwrap(), desc);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_checksums_and_characters() {
+        let desc = "addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)";
+        assert_eq!(strip_checksum(desc).unwrap(), desc);
+        let err = strip_checksum(&format!("{desc}#02wpgw68")).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+        assert!(strip_checksum(&format!("{desc}#")).is_err());
+        assert!(checksum("pkh(é)").is_err());
+    }
+}
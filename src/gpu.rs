@@ -0,0 +1,536 @@
+//! GPU sequence writer: fills a storage buffer with `start + idx` for each
+//! invocation and reads it back through a pair of mappable buffers.
+
+use crate::backend::{CandidateBackend, SLOTS};
+use crate::u256::U256;
+use anyhow::{Result, anyhow};
+use bytemuck::{Pod, Zeroable};
+use futures::channel::oneshot;
+use pollster::block_on;
+use std::borrow::Cow;
+use std::mem::size_of;
+use std::num::NonZeroU64;
+use wgpu::{BufferSlice, BufferUsages, util::DeviceExt};
+
+/// Uniform block passed to `shaders/seq.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct Params {
+    pub start0: u32,
+    pub start1: u32,
+    pub start2: u32,
+    pub start3: u32,
+    pub start4: u32,
+    pub start5: u32,
+    pub start6: u32,
+    pub start7: u32,
+    pub n: u32,
+    pub _pad0: u32,
+    pub _pad1: u32,
+    pub _pad2: u32,
+}
+
+impl Params {
+    /// Parameters for `n` keys starting at `start`.
+    pub fn new(start: U256, n: u32) -> Self {
+        let [
+            start0,
+            start1,
+            start2,
+            start3,
+            start4,
+            start5,
+            start6,
+            start7,
+        ] = start.to_le_words();
+        Self {
+            start0,
+            start1,
+            start2,
+            start3,
+            start4,
+            start5,
+            start6,
+            start7,
+            n,
+            _pad0: 0,
+            _pad1: 0,
+            _pad2: 0,
+        }
+    }
+}
+
+/// Invocations per workgroup in every kernel.
+pub(crate) const WORKGROUP: u32 = 256;
+
+/// Acquire a high-performance adapter and open a device on it, raising the
+/// buffer and dispatch limits to what the adapter supports.
+pub(crate) async fn request_device() -> Result<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or_else(|| anyhow!("No suitable GPU adapter found"))?;
+
+    let supported = adapter.limits();
+    let required_limits = wgpu::Limits {
+        max_storage_buffer_binding_size: supported.max_storage_buffer_binding_size,
+        max_buffer_size: supported.max_buffer_size,
+        max_compute_workgroups_per_dimension: supported.max_compute_workgroups_per_dimension,
+        ..wgpu::Limits::downlevel_defaults()
+    };
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Device"),
+                required_features: wgpu::Features::empty(),
+                required_limits,
+            },
+            None,
+        )
+        .await?;
+    Ok((device, queue))
+}
+
+/// A run of keys `first..first + n` within a batch, dispatched with its own
+/// binding of the output buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Tile {
+    pub first: u32,
+    pub n: u32,
+}
+
+/// Splits batches so that each tile's output fits one storage binding and its
+/// workgroups fit a 2D grid, and bounds the batch by the largest buffer.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Tiling {
+    record_bytes: u64,
+    keys_per_tile: u32,
+    max_groups: u32,
+    max_batch: u32,
+}
+
+impl Tiling {
+    /// Tiling for kernels writing `record_bytes` per key under `limits`. A
+    /// `record_bytes` of zero means the output does not grow with the batch.
+    pub fn new(limits: &wgpu::Limits, record_bytes: u64) -> Self {
+        let max_groups = limits.max_compute_workgroups_per_dimension.max(1);
+        let grid_keys = (max_groups as u64).pow(2) * WORKGROUP as u64;
+        if record_bytes == 0 {
+            let all = grid_keys.min(u32::MAX as u64) as u32;
+            return Self {
+                record_bytes,
+                keys_per_tile: all,
+                max_groups,
+                max_batch: all,
+            };
+        }
+
+        // Tile boundaries must land on a storage offset alignment.
+        let align = limits.min_storage_buffer_offset_alignment.max(1) as u64;
+        let align_keys = align / gcd(align, record_bytes);
+        let per_binding = limits.max_storage_buffer_binding_size as u64 / record_bytes;
+        let keys_per_tile = (per_binding.min(grid_keys) / align_keys * align_keys)
+            .max(align_keys)
+            .min(u32::MAX as u64) as u32;
+        let max_batch = (limits.max_buffer_size / record_bytes).clamp(1, u32::MAX as u64) as u32;
+        Self {
+            record_bytes,
+            keys_per_tile,
+            max_groups,
+            max_batch,
+        }
+    }
+
+    /// Largest batch whose output fits one buffer.
+    pub fn max_batch(&self) -> u32 {
+        self.max_batch
+    }
+
+    /// Byte offset of the first record of `tile`.
+    pub fn offset(&self, tile: Tile) -> u64 {
+        tile.first as u64 * self.record_bytes
+    }
+
+    /// Consecutive tiles covering `0..n`.
+    pub fn tiles(&self, n: u32) -> impl Iterator<Item = Tile> + '_ {
+        (0..n)
+            .step_by(self.keys_per_tile as usize)
+            .map(move |first| Tile {
+                first,
+                n: (n - first).min(self.keys_per_tile),
+            })
+    }
+
+    /// Workgroup grid `(x, y)` for `n` keys; kernels flatten it as
+    /// `gid.x + gid.y * num_workgroups.x * WORKGROUP`.
+    pub fn grid(&self, n: u32) -> (u32, u32) {
+        let groups = n.div_ceil(WORKGROUP).max(1);
+        let x = groups.min(self.max_groups);
+        (x, groups.div_ceil(x))
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// One storage buffer and one readback buffer of `size` bytes per slot.
+pub(crate) fn slot_buffers(
+    device: &wgpu::Device,
+    size: u64,
+) -> ([wgpu::Buffer; SLOTS], [wgpu::Buffer; SLOTS]) {
+    let out_storage = [0, 1].map(|_| {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("out_storage"),
+            size,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    });
+    let readback = [0, 1].map(|_| {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    });
+    (out_storage, readback)
+}
+
+/// Receiver resolved once a readback buffer has been mapped.
+pub type MapReceiver = oneshot::Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// Double-buffered GPU candidate generator.
+///
+/// Slots `0` and `1` each own a storage buffer and a readback buffer so one
+/// batch can be verified on the CPU while the next is being generated.
+pub struct GpuSeq {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_layout: wgpu::BindGroupLayout,
+    out_storage: [wgpu::Buffer; 2],
+    readback: [wgpu::Buffer; 2],
+    capacity: u32,
+    tiling: Tiling,
+    /// Submitted batches awaiting [`CandidateBackend::wait`].
+    pending: [Option<(u64, MapReceiver)>; SLOTS],
+    /// Host copies of completed batches.
+    host: [Vec<u8>; SLOTS],
+}
+
+impl GpuSeq {
+    /// Acquire a high-performance adapter and allocate buffers for `max_batch`
+    /// keys, or as many as the device's largest buffer holds.
+    pub async fn new(max_batch: u32) -> Result<Self> {
+        let (device, queue) = request_device().await?;
+
+        let shader_src = include_str!("../shaders/seq.wgsl");
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("seq.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(shader_src)),
+        });
+
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bind layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("pipeline layout"),
+            bind_group_layouts: &[&bind_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("compute pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "main",
+            compilation_options: Default::default(),
+        });
+
+        let tiling = Tiling::new(&device.limits(), 32);
+        let capacity = max_batch.clamp(1, tiling.max_batch());
+        let (out_storage, readback) = slot_buffers(&device, (capacity as u64) * 32);
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            bind_layout,
+            out_storage,
+            readback,
+            capacity,
+            tiling,
+            pending: [None, None],
+            host: [Vec::new(), Vec::new()],
+        })
+    }
+
+    /// Block until all submitted GPU work has completed.
+    pub fn poll(&self) {
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    /// Release the mapping of readback slot `idx`.
+    pub fn unmap(&self, idx: usize) {
+        self.readback[idx].unmap();
+    }
+
+    /// The first `size` bytes of readback slot `idx`.
+    pub fn slice(&self, idx: usize, size: u64) -> BufferSlice<'_> {
+        self.readback[idx].slice(0..size)
+    }
+
+    /// Generate `n` keys starting at `start` into slot `idx` and request a
+    /// read mapping. Returns the output size in bytes and a receiver that
+    /// resolves once the mapping is ready (after [`GpuSeq::poll`]).
+    ///
+    /// Fails if `n` exceeds [`CandidateBackend::max_batch`].
+    pub fn dispatch_and_map(
+        &mut self,
+        start: U256,
+        n: u32,
+        idx: usize,
+    ) -> Result<(u64, MapReceiver)> {
+        let out_u32_len = (n as usize) * 8;
+        let out_size_bytes = (out_u32_len * size_of::<u32>()) as u64;
+
+        if n > self.tiling.max_batch() {
+            return Err(anyhow!(
+                "batch of {n} keys exceeds the device limit of {}",
+                self.tiling.max_batch()
+            ));
+        }
+        if n > self.capacity {
+            (self.out_storage, self.readback) = slot_buffers(&self.device, (n as u64) * 32);
+            self.capacity = n;
+        }
+
+        let bind_groups: Vec<_> = self
+            .tiling
+            .tiles(n)
+            .map(|tile| {
+                let params = Params::new(start + tile.first as u64, tile.n);
+                let params_buf =
+                    self.device
+                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                            label: Some("params"),
+                            contents: bytemuck::bytes_of(&params),
+                            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+                        });
+                let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("bind group"),
+                    layout: &self.bind_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: params_buf.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: &self.out_storage[idx],
+                                offset: self.tiling.offset(tile),
+                                size: NonZeroU64::new(tile.n as u64 * 32),
+                            }),
+                        },
+                    ],
+                });
+                (tile, bind_group)
+            })
+            .collect();
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("encoder"),
+            });
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("seq pass"),
+                timestamp_writes: None,
+            });
+            cpass.set_pipeline(&self.pipeline);
+            for (tile, bind_group) in &bind_groups {
+                let (x, y) = self.tiling.grid(tile.n);
+                cpass.set_bind_group(0, bind_group, &[]);
+                cpass.dispatch_workgroups(x, y, 1);
+            }
+        }
+        encoder.copy_buffer_to_buffer(
+            &self.out_storage[idx],
+            0,
+            &self.readback[idx],
+            0,
+            out_size_bytes,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = self.readback[idx].slice(0..out_size_bytes);
+        let (sender, receiver) = oneshot::channel();
+        slice.map_async(wgpu::MapMode::Read, move |r| {
+            let _ = sender.send(r);
+        });
+        Ok((out_size_bytes, receiver))
+    }
+}
+
+impl CandidateBackend for GpuSeq {
+    fn max_batch(&self) -> u32 {
+        self.tiling.max_batch()
+    }
+
+    fn submit(&mut self, slot: usize, start: U256, n: u32) -> Result<()> {
+        let pending = self.dispatch_and_map(start, n, slot)?;
+        self.pending[slot] = Some(pending);
+        Ok(())
+    }
+
+    fn wait(&mut self, slot: usize) -> Result<()> {
+        let (size, recv) = self.pending[slot]
+            .take()
+            .ok_or_else(|| anyhow!("no batch submitted to slot {slot}"))?;
+        self.poll();
+        block_on(recv)??;
+        let host = &mut self.host[slot];
+        host.resize(size as usize, 0);
+        {
+            let data = self.readback[slot].slice(0..size).get_mapped_range();
+            host.copy_from_slice(&data);
+        }
+        self.unmap(slot);
+        Ok(())
+    }
+
+    fn results(&self, slot: usize) -> &[u8] {
+        &self.host[slot]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::CpuSeq;
+    use serial_test::file_serial;
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_seq_resizes() {
+        let mut gpu = block_on(GpuSeq::new(1)).expect("gpu init");
+        gpu.submit(0, U256::ZERO, 1).expect("submit");
+        gpu.wait(0).expect("seq");
+        assert_eq!(gpu.results(0).len(), 32);
+        gpu.submit(0, U256::ZERO, 2).expect("submit");
+        gpu.wait(0).expect("seq");
+        assert_eq!(gpu.results(0).len(), 64);
+    }
+
+    fn limits(binding: u32, groups: u32, buffer: u64) -> wgpu::Limits {
+        wgpu::Limits {
+            max_storage_buffer_binding_size: binding,
+            max_compute_workgroups_per_dimension: groups,
+            max_buffer_size: buffer,
+            ..wgpu::Limits::downlevel_defaults()
+        }
+    }
+
+    #[test]
+    fn tiling_covers_batch_with_aligned_bounded_tiles() {
+        // 36-byte records: 64 keys per 256-byte alignment step.
+        let t = Tiling::new(&limits(10_000, 65_535, 1 << 30), 36);
+        let tiles: Vec<_> = t.tiles(1000).collect();
+        assert_eq!(tiles[0], Tile { first: 0, n: 256 });
+        assert_eq!(tiles.last(), Some(&Tile { first: 768, n: 232 }));
+        assert_eq!(tiles.iter().map(|t| t.n).sum::<u32>(), 1000);
+        for tile in &tiles {
+            assert_eq!(t.offset(*tile) % 256, 0);
+            assert!(tile.n as u64 * 36 <= 10_000);
+        }
+    }
+
+    #[test]
+    fn tiling_uses_2d_grid_past_the_dimension_limit() {
+        let t = Tiling::new(&limits(u32::MAX, 65_535, u64::MAX), 32);
+        // 20M keys need 78125 workgroups, more than fit one dimension.
+        assert_eq!(t.grid(20_000_000), (65_535, 2));
+        assert_eq!(t.grid(1), (1, 1));
+
+        let small = Tiling::new(&limits(u32::MAX, 4, u64::MAX), 32);
+        assert_eq!(small.tiles(10_000).count(), 3);
+        for tile in small.tiles(10_000) {
+            let (x, y) = small.grid(tile.n);
+            assert!(x <= 4 && y <= 4);
+            assert!(x * y * WORKGROUP >= tile.n);
+        }
+    }
+
+    #[test]
+    fn tiling_clamps_batch_to_buffer_size() {
+        let t = Tiling::new(&limits(1 << 27, 65_535, 1 << 28), 32);
+        assert_eq!(t.max_batch(), 1 << 23);
+        assert_eq!(
+            Tiling::new(&limits(1 << 27, 65_535, 1 << 28), 0).max_batch(),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_seq_matches_cpu_seq_across_tiles() {
+        let start = U256::from_u64(u64::MAX - 700);
+        let mut gpu = block_on(GpuSeq::new(5000)).expect("gpu init");
+        gpu.tiling = Tiling::new(&limits(1024 * 32, 2, u64::MAX), 32);
+        gpu.submit(0, start, 5000).expect("submit");
+        gpu.wait(0).expect("seq");
+        let mut expected = Vec::new();
+        CpuSeq::generate(start, 5000, &mut expected);
+        assert_eq!(gpu.results(0), &expected[..]);
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_seq_matches_cpu_seq() {
+        let start = U256::from_le_words([u32::MAX - 2, u32::MAX, 7, 0, 0, 0, 0, 0]);
+        let mut gpu = block_on(GpuSeq::new(1024)).expect("gpu init");
+        gpu.submit(1, start, 1000).expect("submit");
+        gpu.wait(1).expect("seq");
+        let mut expected = Vec::new();
+        CpuSeq::generate(start, 1000, &mut expected);
+        assert_eq!(gpu.results(1), &expected[..]);
+    }
+}
@@ -0,0 +1,842 @@
+//! GPU public-key generator: runs secp256k1 point arithmetic in
+//! `shaders/pubkey.wgsl` and returns either compressed public keys or their
+//! HASH160, leaving at most hashing and comparison to the CPU. With targets
+//! it compares on the GPU too and reads back only the indices that matched.
+
+use crate::backend::{BatchFormat, CandidateBackend, SLOTS};
+use crate::gpu::{MapReceiver, Tiling, request_device, slot_buffers};
+use crate::search::PubkeyFormat;
+use crate::target::TargetSet;
+use crate::u256::U256;
+use anyhow::{Result, anyhow};
+use bytemuck::{Pod, Zeroable};
+use futures::channel::oneshot;
+use pollster::block_on;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use std::borrow::Cow;
+use std::num::NonZeroU64;
+use wgpu::{BufferUsages, util::DeviceExt};
+
+/// Order `n` of the secp256k1 group; valid private keys are `1..n`.
+pub const CURVE_ORDER: U256 = U256::from_limbs([
+    0xBFD2_5E8C_D036_4141,
+    0xBAAE_DCE6_AF48_A03B,
+    0xFFFF_FFFF_FFFF_FFFE,
+    0xFFFF_FFFF_FFFF_FFFF,
+]);
+
+/// `secp256k1.wgsl` and `hash.wgsl` followed by the kernel that uses them.
+const PUBKEY_WGSL: &str = concat!(
+    include_str!("../shaders/secp256k1.wgsl"),
+    "\n",
+    include_str!("../shaders/hash.wgsl"),
+    "\n",
+    include_str!("../shaders/pubkey.wgsl")
+);
+
+/// `EcParams::mode` values understood by the kernel.
+const MODE_PUBKEY: u32 = 0;
+const MODE_HASH160: u32 = 1;
+
+/// `EcParams::forms` bits: which public keys `find` hashes.
+const FORM_COMPRESSED: u32 = 1;
+const FORM_UNCOMPRESSED: u32 = 2;
+/// Also hash the P2WPKH redeem script of the compressed key.
+const FORM_NESTED_SEGWIT: u32 = 4;
+
+/// Capacity of the hit buffer used with [`BatchFormat::HitIndices`]. Matches
+/// beyond this in one batch are counted by the kernel but not returned, so
+/// an overflowing batch hands back every index for the CPU to verify.
+pub const MAX_HITS: u32 = 1024;
+
+/// Number of `2^i * G` points uploaded; one per bit of the in-batch index.
+const TABLE_POINTS: u32 = 32;
+
+/// Uniform block passed to `shaders/pubkey.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct EcParams {
+    pub base_x: [[u32; 4]; 2],
+    pub base_y: [[u32; 4]; 2],
+    pub n: u32,
+    pub lo: u32,
+    pub hi: u32,
+    pub mode: u32,
+    /// `FORM_*` bits, for `find`.
+    pub forms: u32,
+    /// Index of the tile's first key in the batch, added to the hits `find`
+    /// reports.
+    pub first: u32,
+    pub _pad: [u32; 2],
+}
+
+/// Double-buffered GPU backend producing [`BatchFormat::CompressedPubKeys`],
+/// [`BatchFormat::Hash160`] or [`BatchFormat::HitIndices`] batches.
+pub struct GpuEc {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_layout: wgpu::BindGroupLayout,
+    table: wgpu::Buffer,
+    out_storage: [wgpu::Buffer; SLOTS],
+    readback: [wgpu::Buffer; SLOTS],
+    capacity: u32,
+    tiling: Tiling,
+    format: BatchFormat,
+    /// Sorted target HASH160s for `find`; a placeholder otherwise.
+    wanted: wgpu::Buffer,
+    forms: u32,
+    pending: [Option<(u32, MapReceiver)>; SLOTS],
+    host: [Vec<u8>; SLOTS],
+    secp: Secp256k1<secp256k1::All>,
+}
+
+impl GpuEc {
+    /// Acquire a GPU and allocate buffers for `max_batch` keys producing
+    /// `format` records. Fails for [`BatchFormat::Keys`] and, since it needs
+    /// targets, [`BatchFormat::HitIndices`]; see [`GpuEc::with_targets`].
+    pub async fn new(max_batch: u32, format: BatchFormat) -> Result<Self> {
+        if matches!(format, BatchFormat::Keys | BatchFormat::HitIndices) {
+            return Err(anyhow!("GpuEc::new cannot produce {format:?} batches"));
+        }
+        Self::build(
+            max_batch,
+            format,
+            &TargetSet::default(),
+            PubkeyFormat::Compressed,
+        )
+        .await
+    }
+
+    /// Acquire a GPU that hashes every key in the forms `pubkeys` selects,
+    /// and the redeem script of the compressed key if there are P2SH-P2WPKH
+    /// targets, looks the results up in `targets` itself and produces
+    /// [`BatchFormat::HitIndices`] batches. Fails if `targets` is empty or
+    /// holds anything but [`Target::Hash160`](crate::target::Target::Hash160)
+    /// targets.
+    pub async fn with_targets(
+        max_batch: u32,
+        targets: &TargetSet,
+        pubkeys: PubkeyFormat,
+    ) -> Result<Self> {
+        if targets.is_empty() {
+            return Err(anyhow!("GpuEc::with_targets needs at least one target"));
+        }
+        if !targets.hash160_only() {
+            return Err(anyhow!("GpuEc::with_targets only matches HASH160 targets"));
+        }
+        Self::build(max_batch, BatchFormat::HitIndices, targets, pubkeys).await
+    }
+
+    async fn build(
+        max_batch: u32,
+        format: BatchFormat,
+        targets: &TargetSet,
+        pubkeys: PubkeyFormat,
+    ) -> Result<Self> {
+        let find = format == BatchFormat::HitIndices;
+        let (device, queue) = request_device().await?;
+        let secp = Secp256k1::new();
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("pubkey.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(PUBKEY_WGSL)),
+        });
+
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ec bind layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                // Records for `main`, the hit buffer and targets for `find`.
+                storage(if find { 3 } else { 2 }, false),
+                storage(4, true),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("ec pipeline layout"),
+            bind_group_layouts: &[&bind_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("ec pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: if find { "find" } else { "main" },
+            compilation_options: Default::default(),
+        });
+
+        let table = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("g_pow2"),
+            contents: bytemuck::cast_slice(&pow2_table(&secp)),
+            usage: BufferUsages::STORAGE,
+        });
+
+        let tiling = Tiling::new(&device.limits(), key_bytes(format));
+        let capacity = max_batch.clamp(1, tiling.max_batch());
+        let (out_storage, readback) = slot_buffers(&device, record_bytes(format, capacity));
+
+        let wanted = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("wanted"),
+            contents: bytemuck::cast_slice(&wanted_table(&targets.hash160s())),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            bind_layout,
+            table,
+            out_storage,
+            readback,
+            capacity,
+            tiling,
+            format,
+            wanted,
+            forms: forms(targets, pubkeys),
+            pending: [None, None],
+            host: [Vec::new(), Vec::new()],
+            secp,
+        })
+    }
+}
+
+impl GpuEc {
+    /// Kernel parameters for the tile of `n` keys starting `first` keys into
+    /// a batch at `start`.
+    fn params(&self, start: U256, first: u32, n: u32) -> EcParams {
+        let start = start + first as u64;
+        let (lo, hi) = valid_window(start, n);
+        let base = if lo < hi {
+            start + lo as u64
+        } else {
+            U256::ONE
+        };
+        let (base_x, base_y) = affine_words(&self.secp, base);
+        EcParams {
+            base_x: split_words(base_x),
+            base_y: split_words(base_y),
+            n,
+            lo,
+            hi,
+            mode: match self.format {
+                BatchFormat::Hash160 => MODE_HASH160,
+                _ => MODE_PUBKEY,
+            },
+            forms: self.forms,
+            first,
+            _pad: [0; 2],
+        }
+    }
+}
+
+impl CandidateBackend for GpuEc {
+    fn format(&self) -> BatchFormat {
+        self.format
+    }
+
+    fn max_batch(&self) -> u32 {
+        self.tiling.max_batch()
+    }
+
+    fn submit(&mut self, slot: usize, start: U256, n: u32) -> Result<()> {
+        if n > self.tiling.max_batch() {
+            return Err(anyhow!(
+                "batch of {n} keys exceeds the device limit of {}",
+                self.tiling.max_batch()
+            ));
+        }
+        if n > self.capacity {
+            (self.out_storage, self.readback) =
+                slot_buffers(&self.device, record_bytes(self.format, n));
+            self.capacity = n;
+        }
+        let out_size = record_bytes(self.format, n);
+
+        let out_binding = if self.format == BatchFormat::HitIndices {
+            3
+        } else {
+            2
+        };
+        let bind_groups: Vec<_> = self
+            .tiling
+            .tiles(n)
+            .map(|tile| {
+                let params = self.params(start, tile.first, tile.n);
+                let params_buf =
+                    self.device
+                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                            label: Some("ec params"),
+                            contents: bytemuck::bytes_of(&params),
+                            usage: BufferUsages::UNIFORM,
+                        });
+                // Every tile appends to the one hit buffer.
+                let (offset, out_size) = match self.format {
+                    BatchFormat::HitIndices => (0, out_size),
+                    format => (self.tiling.offset(tile), record_bytes(format, tile.n)),
+                };
+                let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("ec bind group"),
+                    layout: &self.bind_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: params_buf.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: self.table.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: out_binding,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: &self.out_storage[slot],
+                                offset,
+                                size: NonZeroU64::new(out_size),
+                            }),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 4,
+                            resource: self.wanted.as_entire_binding(),
+                        },
+                    ],
+                });
+                (tile, bind_group)
+            })
+            .collect();
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("ec encoder"),
+            });
+        if self.format == BatchFormat::HitIndices {
+            encoder.clear_buffer(&self.out_storage[slot], 0, Some(4));
+        }
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("ec pass"),
+                timestamp_writes: None,
+            });
+            cpass.set_pipeline(&self.pipeline);
+            for (tile, bind_group) in &bind_groups {
+                let (x, y) = self.tiling.grid(tile.n);
+                cpass.set_bind_group(0, bind_group, &[]);
+                cpass.dispatch_workgroups(x, y, 1);
+            }
+        }
+        encoder.copy_buffer_to_buffer(
+            &self.out_storage[slot],
+            0,
+            &self.readback[slot],
+            0,
+            out_size,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = oneshot::channel();
+        self.readback[slot]
+            .slice(0..out_size)
+            .map_async(wgpu::MapMode::Read, move |r| {
+                let _ = sender.send(r);
+            });
+        self.pending[slot] = Some((n, receiver));
+        Ok(())
+    }
+
+    fn wait(&mut self, slot: usize) -> Result<()> {
+        let (n, recv) = self.pending[slot]
+            .take()
+            .ok_or_else(|| anyhow!("no batch submitted to slot {slot}"))?;
+        self.device.poll(wgpu::Maintain::Wait);
+        block_on(recv)??;
+
+        let host = &mut self.host[slot];
+        {
+            let data = self.readback[slot]
+                .slice(0..record_bytes(self.format, n))
+                .get_mapped_range();
+            if self.format == BatchFormat::HitIndices {
+                read_hit_indices(&data, n, host);
+            } else if self.format == BatchFormat::Hash160 {
+                host.resize(n as usize * 20, 0);
+                host.copy_from_slice(&data);
+            } else {
+                host.resize(n as usize * 33, 0);
+                // Drop the three padding bytes after each prefix: 36-byte records -> 33.
+                for (rec, out) in data.chunks_exact(36).zip(host.chunks_exact_mut(33)) {
+                    out[0] = rec[0];
+                    out[1..].copy_from_slice(&rec[4..]);
+                }
+            }
+        }
+        self.readback[slot].unmap();
+        Ok(())
+    }
+
+    fn results(&self, slot: usize) -> &[u8] {
+        &self.host[slot]
+    }
+}
+
+/// Copy the hit indices of a [`BatchFormat::HitIndices`] readback of `n`
+/// keys into `host`. Word 0 is the match count and the indices follow; when
+/// the count exceeds [`MAX_HITS`] some matches were not recorded, so every
+/// index of the batch is returned for the CPU to verify.
+fn read_hit_indices(data: &[u8], n: u32, host: &mut Vec<u8>) {
+    let count = u32::from_le_bytes(data[..4].try_into().expect("4 bytes"));
+    host.clear();
+    if count > MAX_HITS {
+        host.extend((0..n).flat_map(u32::to_le_bytes));
+    } else {
+        host.extend_from_slice(&data[4..4 + count as usize * 4]);
+    }
+}
+
+/// Bytes the kernel writes per key: 9 words per public key (a prefix word
+/// and eight words of x), 5 words per HASH160, or none when only hits are
+/// written.
+fn key_bytes(format: BatchFormat) -> u64 {
+    match format {
+        BatchFormat::HitIndices => 0,
+        BatchFormat::Hash160 => 5 * 4,
+        _ => 9 * 4,
+    }
+}
+
+/// Bytes the kernel writes for a batch of `n`; with hits only, a count word
+/// and [`MAX_HITS`] indices regardless of `n`.
+fn record_bytes(format: BatchFormat, n: u32) -> u64 {
+    match format {
+        BatchFormat::HitIndices => (1 + MAX_HITS as u64) * 4,
+        format => n as u64 * key_bytes(format),
+    }
+}
+
+/// In-batch indices `[lo, hi)` whose keys `start + idx` lie in `1..n`.
+fn valid_window(start: U256, n: u32) -> (u32, u32) {
+    if start >= CURVE_ORDER {
+        return (0, 0);
+    }
+    let lo = if start.is_zero() { n.min(1) } else { 0 };
+    let room = CURVE_ORDER - start;
+    let hi = room.to_u64().map_or(n, |r| r.min(n as u64) as u32);
+    (lo, hi.max(lo))
+}
+
+/// Affine coordinates of `k * G` as little-endian limbs. `k` must be in `1..n`.
+fn affine_words(secp: &Secp256k1<secp256k1::All>, k: U256) -> ([u32; 8], [u32; 8]) {
+    let sk = SecretKey::from_slice(&k.to_be_bytes()).expect("key in 1..n");
+    let pk = PublicKey::from_secret_key(secp, &sk).serialize_uncompressed();
+    let x: [u8; 32] = pk[1..33].try_into().expect("32 bytes");
+    let y: [u8; 32] = pk[33..65].try_into().expect("32 bytes");
+    (
+        U256::from_be_bytes(x).to_le_words(),
+        U256::from_be_bytes(y).to_le_words(),
+    )
+}
+
+/// `2^i * G` for `i < 32`, each as x limbs then y limbs.
+fn pow2_table(secp: &Secp256k1<secp256k1::All>) -> Vec<u32> {
+    let mut table = Vec::with_capacity(TABLE_POINTS as usize * 16);
+    for i in 0..TABLE_POINTS {
+        let (x, y) = affine_words(secp, U256::ONE << i);
+        table.extend_from_slice(&x);
+        table.extend_from_slice(&y);
+    }
+    table
+}
+
+/// `FORM_*` bits for searching `targets` in the forms `pubkeys` selects.
+fn forms(targets: &TargetSet, pubkeys: PubkeyFormat) -> u32 {
+    let mut forms = 0;
+    if pubkeys.compressed() {
+        forms |= FORM_COMPRESSED;
+        if targets.has_nested_segwit() {
+            forms |= FORM_NESTED_SEGWIT;
+        }
+    }
+    if pubkeys.uncompressed() {
+        forms |= FORM_UNCOMPRESSED;
+    }
+    forms
+}
+
+/// `targets` as 5 little-endian words each, sorted the way the kernel's
+/// binary search compares them. Without targets, one zero entry stands in so
+/// the binding is never empty.
+fn wanted_table(targets: &[[u8; 20]]) -> Vec<u32> {
+    let mut words: Vec<[u32; 5]> = targets
+        .iter()
+        .map(|h| {
+            let mut w = [0u32; 5];
+            for (w, b) in w.iter_mut().zip(h.chunks_exact(4)) {
+                *w = u32::from_le_bytes(b.try_into().expect("4 bytes"));
+            }
+            w
+        })
+        .collect();
+    words.sort_unstable();
+    words.dedup();
+    if words.is_empty() {
+        words.push([0; 5]);
+    }
+    words.concat()
+}
+
+fn split_words(w: [u32; 8]) -> [[u32; 4]; 2] {
+    [[w[0], w[1], w[2], w[3]], [w[4], w[5], w[6], w[7]]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{hash160, p2wpkh_script};
+    use crate::search::verify_hits;
+    use crate::target::{AddressKind, Target};
+    use serial_test::file_serial;
+
+    #[test]
+    fn curve_order_matches_secp256k1() {
+        assert!(SecretKey::from_slice(&(CURVE_ORDER - 1).to_be_bytes()).is_ok());
+        assert!(SecretKey::from_slice(&CURVE_ORDER.to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn valid_window_excludes_zero_and_keys_past_the_order() {
+        assert_eq!(valid_window(U256::ZERO, 10), (1, 10));
+        assert_eq!(valid_window(U256::ONE, 10), (0, 10));
+        assert_eq!(valid_window(CURVE_ORDER - 3, 10), (0, 3));
+        assert_eq!(valid_window(CURVE_ORDER, 10), (0, 0));
+        assert_eq!(valid_window(U256::MAX - 2, 3), (0, 0));
+    }
+
+    /// Compressed pubkey for key `k` per the `secp256k1` crate, or zeros.
+    fn expected(secp: &Secp256k1<secp256k1::All>, k: U256) -> [u8; 33] {
+        match SecretKey::from_slice(&k.to_be_bytes()) {
+            Ok(sk) => PublicKey::from_secret_key(secp, &sk).serialize(),
+            Err(_) => [0; 33],
+        }
+    }
+
+    /// Record the backend should produce for key `k` in its format.
+    fn expected_record(secp: &Secp256k1<secp256k1::All>, format: BatchFormat, k: U256) -> Vec<u8> {
+        let pk = expected(secp, k);
+        match format {
+            BatchFormat::Hash160 if pk[0] == 0 => vec![0; 20],
+            BatchFormat::Hash160 => hash160(&pk).to_vec(),
+            _ => pk.to_vec(),
+        }
+    }
+
+    fn check_range(gpu: &mut GpuEc, start: U256, n: u32) {
+        let secp = Secp256k1::new();
+        let format = gpu.format();
+        let len = format.record_len();
+        gpu.submit(0, start, n).expect("submit");
+        gpu.wait(0).expect("wait");
+        let out = gpu.results(0);
+        assert_eq!(out.len(), n as usize * len);
+        for (i, rec) in out.chunks_exact(len).enumerate() {
+            let k = start.wrapping_add(U256::from_u64(i as u64));
+            assert_eq!(rec, &expected_record(&secp, format, k)[..], "key {k:#x}");
+        }
+    }
+
+    #[test]
+    fn gpu_ec_rejects_key_format() {
+        assert!(block_on(GpuEc::new(1, BatchFormat::Keys)).is_err());
+        assert!(
+            block_on(GpuEc::with_targets(
+                1,
+                &TargetSet::default(),
+                PubkeyFormat::Both
+            ))
+            .is_err()
+        );
+    }
+
+    /// P2PKH targets for `hashes`.
+    fn key_targets(hashes: &[[u8; 20]]) -> TargetSet {
+        hashes.iter().copied().map(Target::from_hash160).collect()
+    }
+
+    #[test]
+    fn forms_hash_redeem_scripts_only_for_nested_targets() {
+        let p2pkh = key_targets(&[[1; 20]]);
+        let nested = TargetSet::from(Target::new(AddressKind::P2shP2wpkh, [1; 20]));
+        assert_eq!(
+            forms(&p2pkh, PubkeyFormat::Both),
+            FORM_COMPRESSED | FORM_UNCOMPRESSED
+        );
+        assert_eq!(
+            forms(&nested, PubkeyFormat::Compressed),
+            FORM_COMPRESSED | FORM_NESTED_SEGWIT
+        );
+        assert_eq!(
+            forms(&nested, PubkeyFormat::Uncompressed),
+            FORM_UNCOMPRESSED
+        );
+    }
+
+    #[test]
+    fn wanted_table_sorts_by_little_endian_words() {
+        let mut a = [0u8; 20];
+        a[3] = 1; // word 0 = 0x0100_0000
+        let mut b = [0u8; 20];
+        b[0] = 2; // word 0 = 2
+        let table = wanted_table(&[a, b, a]);
+        assert_eq!(table.len(), 10);
+        assert_eq!(&table[..5], &[2, 0, 0, 0, 0]);
+        assert_eq!(table[5], 0x0100_0000);
+        assert_eq!(wanted_table(&[]), vec![0; 5]);
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_hash160_matches_cpu() {
+        let mut gpu = block_on(GpuEc::new(512, BatchFormat::Hash160)).expect("gpu init");
+        check_range(&mut gpu, U256::ZERO, 300);
+        check_range(
+            &mut gpu,
+            U256::from_hex("1f_0e4d_7c3b_2a19_8f7e_6d5c").unwrap(),
+            512,
+        );
+        check_range(&mut gpu, CURVE_ORDER - 5, 10);
+    }
+
+    fn hit_indices(gpu: &mut GpuEc, slot: usize, start: U256, n: u32) -> Vec<u32> {
+        gpu.submit(slot, start, n).expect("submit");
+        gpu.wait(slot).expect("wait");
+        let mut idx: Vec<u32> = gpu
+            .results(slot)
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        idx.sort();
+        idx
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_reports_only_matching_indices() {
+        let secp = Secp256k1::new();
+        let key = U256::from_u64(0x2_0d45);
+        let target = hash160(&expected(&secp, key));
+        let mut gpu = block_on(GpuEc::with_targets(
+            1024,
+            &key_targets(&[target]),
+            PubkeyFormat::Compressed,
+        ))
+        .expect("gpu init");
+        assert_eq!(gpu.format(), BatchFormat::HitIndices);
+
+        assert_eq!(hit_indices(&mut gpu, 0, key - 100, 1024), vec![100]);
+        // Both slots; the counter is reset between batches.
+        assert_eq!(hit_indices(&mut gpu, 1, key + 1, 1024), vec![]);
+        assert_eq!(hit_indices(&mut gpu, 0, key, 1), vec![0]);
+        assert_eq!(hit_indices(&mut gpu, 1, key - 1023, 1024), vec![1023]);
+    }
+
+    #[test]
+    fn overflowing_hit_buffer_returns_the_whole_batch() {
+        let readback = |count: u32, indices: &[u32]| -> Vec<u8> {
+            let mut data = count.to_le_bytes().to_vec();
+            data.extend(indices.iter().flat_map(|i| i.to_le_bytes()));
+            data.resize(record_bytes(BatchFormat::HitIndices, 0) as usize, 0);
+            data
+        };
+        let words = |host: &[u8]| -> Vec<u32> {
+            host.chunks_exact(4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                .collect()
+        };
+        let mut host = vec![0xaa; 8];
+        read_hit_indices(&readback(2, &[9, 4]), 4096, &mut host);
+        assert_eq!(words(&host), [9, 4]);
+
+        let full: Vec<u32> = (0..MAX_HITS).collect();
+        read_hit_indices(&readback(MAX_HITS, &full), 4096, &mut host);
+        assert_eq!(words(&host), full);
+        read_hit_indices(&readback(MAX_HITS + 1, &full), 4096, &mut host);
+        assert_eq!(words(&host), (0..4096).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_reports_every_hit_past_the_hit_buffer() {
+        let secp = Secp256k1::new();
+        let start = U256::from_u64(0x1_0000);
+        let hits = MAX_HITS as u64 + 100;
+        let targets: Vec<[u8; 20]> = (0..hits)
+            .map(|i| hash160(&expected(&secp, start + i)))
+            .collect();
+        let targets = key_targets(&targets);
+        let mut gpu = block_on(GpuEc::with_targets(
+            2048,
+            &targets,
+            PubkeyFormat::Compressed,
+        ))
+        .expect("gpu init");
+        assert_eq!(
+            hit_indices(&mut gpu, 0, start, 2048),
+            (0..2048).collect::<Vec<_>>()
+        );
+        let found = verify_hits(
+            gpu.results(0),
+            start,
+            &secp,
+            &targets,
+            PubkeyFormat::Compressed,
+        );
+        assert_eq!(found.len() as u64, hits);
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_looks_up_every_target() {
+        let secp = Secp256k1::new();
+        let start = U256::from_u64(0x4_0000);
+        let keys = [0x4_0003u64, 0x4_0100, 0x4_0101, 0x4_03ff];
+        let mut targets: Vec<[u8; 20]> = keys
+            .iter()
+            .map(|&k| hash160(&expected(&secp, U256::from_u64(k))))
+            .collect();
+        // Targets outside the batch must not match anything.
+        targets.push(hash160(&expected(&secp, U256::from_u64(7))));
+        targets.push([0xff; 20]);
+        let mut gpu = block_on(GpuEc::with_targets(
+            1024,
+            &key_targets(&targets),
+            PubkeyFormat::Compressed,
+        ))
+        .expect("gpu init");
+        assert_eq!(
+            hit_indices(&mut gpu, 0, start, 1024),
+            vec![0x3, 0x100, 0x101, 0x3ff]
+        );
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_hashes_the_selected_pubkey_forms() {
+        let secp = Secp256k1::new();
+        let uncompressed = |k: u64| {
+            let sk = SecretKey::from_slice(&U256::from_u64(k).to_be_bytes()).unwrap();
+            hash160(&PublicKey::from_secret_key(&secp, &sk).serialize_uncompressed())
+        };
+        let compressed = |k: u64| hash160(&expected(&secp, U256::from_u64(k)));
+        let targets = key_targets(&[uncompressed(0x1_0005), compressed(0x1_0009)]);
+        let start = U256::from_u64(0x1_0000);
+        for (pubkeys, want) in [
+            (PubkeyFormat::Compressed, vec![9]),
+            (PubkeyFormat::Uncompressed, vec![5]),
+            (PubkeyFormat::Both, vec![5, 9]),
+        ] {
+            let mut gpu = block_on(GpuEc::with_targets(256, &targets, pubkeys)).expect("gpu init");
+            assert_eq!(hit_indices(&mut gpu, 0, start, 256), want, "{pubkeys}");
+        }
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_hashes_nested_segwit_redeem_scripts() {
+        let secp = Secp256k1::new();
+        let key_hash = hash160(&expected(&secp, U256::from_u64(0x1_0007)));
+        let targets: TargetSet = [
+            Target::new(AddressKind::P2shP2wpkh, hash160(&p2wpkh_script(&key_hash))),
+            Target::from_hash160(hash160(&expected(&secp, U256::from_u64(0x1_0002)))),
+        ]
+        .into_iter()
+        .collect();
+        let start = U256::from_u64(0x1_0000);
+        let mut gpu = block_on(GpuEc::with_targets(256, &targets, PubkeyFormat::Compressed))
+            .expect("gpu init");
+        assert_eq!(hit_indices(&mut gpu, 0, start, 256), vec![2, 7]);
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_matches_across_tiles() {
+        let limits = wgpu::Limits {
+            max_storage_buffer_binding_size: 256 * 36,
+            max_compute_workgroups_per_dimension: 1,
+            ..wgpu::Limits::downlevel_defaults()
+        };
+        for format in [BatchFormat::CompressedPubKeys, BatchFormat::Hash160] {
+            let mut gpu = block_on(GpuEc::new(1000, format)).expect("gpu init");
+            gpu.tiling = Tiling::new(&limits, key_bytes(format));
+            check_range(&mut gpu, U256::ZERO, 1000);
+            check_range(&mut gpu, CURVE_ORDER - 600, 1000);
+        }
+
+        // Hit indices count from the start of the batch, not of the tile.
+        let secp = Secp256k1::new();
+        let start = U256::from_u64(0x1_0000);
+        let wanted = [3, 255, 256, 700, 999];
+        let targets: Vec<[u8; 20]> = wanted
+            .iter()
+            .map(|&i| hash160(&expected(&secp, start + i as u64)))
+            .collect();
+        let mut gpu = block_on(GpuEc::with_targets(
+            1000,
+            &key_targets(&targets),
+            PubkeyFormat::Compressed,
+        ))
+        .expect("gpu init");
+        gpu.tiling = Tiling::new(&limits, key_bytes(BatchFormat::CompressedPubKeys));
+        assert!(gpu.tiling.tiles(1000).count() > 1);
+        assert_eq!(hit_indices(&mut gpu, 0, start, 1000), wanted);
+    }
+
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn gpu_ec_matches_secp256k1_crate() {
+        let mut gpu = block_on(GpuEc::new(512, BatchFormat::CompressedPubKeys)).expect("gpu init");
+        // Small keys: exercises the doubling branch (base == j * G).
+        check_range(&mut gpu, U256::ZERO, 300);
+        // Straddles the 32-bit limb boundary of the key.
+        check_range(&mut gpu, U256::from_u64(0xFFFF_FF00), 512);
+        // Large, arbitrary keys.
+        check_range(
+            &mut gpu,
+            U256::from_hex(
+                "7a3f_91c2_55e0_1d4b_8c6a_2f93_0be7_4d11_c9a8_3e56_71f0_2b4d_9e8c_5a13_6f27_d0e4",
+            )
+            .unwrap(),
+            257,
+        );
+        // Runs past the group order into invalid keys.
+        check_range(&mut gpu, CURVE_ORDER - 5, 10);
+    }
+}
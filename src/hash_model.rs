@@ -0,0 +1,380 @@
+//! Word-level CPU model of `shaders/hash.wgsl`.
+//!
+//! Messages are packed into big-endian `u32` words exactly as the kernels
+//! pack them, and every step mirrors the WGSL so a disagreement with the
+//! `sha2`/`ripemd` crates points at the algorithm rather than the packing.
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const RMD_R: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5,
+    2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8, 12, 4,
+    13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+const RMD_RP: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12,
+    4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11, 15, 0, 5,
+    12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+const RMD_S: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15,
+    9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14, 15, 14,
+    15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+const RMD_SP: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12,
+    7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11, 14, 14,
+    6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+const RMD_K: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+const RMD_KP: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// Pack bytes into big-endian words, zero-filling the last word.
+pub fn pack_be(msg: &[u8]) -> Vec<u32> {
+    msg.chunks(4)
+        .map(|c| {
+            let mut b = [0u8; 4];
+            b[..c.len()].copy_from_slice(c);
+            u32::from_be_bytes(b)
+        })
+        .collect()
+}
+
+pub fn sha256_compress(state: [u32; 8], block: [u32; 16]) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    w[..16].copy_from_slice(&block);
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    let mut out = state;
+    for (o, v) in out.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *o = o.wrapping_add(v);
+    }
+    out
+}
+
+/// SHA-256 of a 22-byte message packed into 6 big-endian words.
+pub fn sha256_22(m: [u32; 6]) -> [u32; 8] {
+    let mut block = [0u32; 16];
+    block[..5].copy_from_slice(&m[..5]);
+    block[5] = (m[5] & 0xffff_0000) | 0x0000_8000;
+    block[15] = 22 * 8;
+    sha256_compress(SHA256_IV, block)
+}
+
+/// SHA-256 of a 33-byte message packed into 9 big-endian words.
+pub fn sha256_33(m: [u32; 9]) -> [u32; 8] {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(&m[..8]);
+    block[8] = (m[8] & 0xff00_0000) | 0x0080_0000;
+    block[15] = 33 * 8;
+    sha256_compress(SHA256_IV, block)
+}
+
+/// SHA-256 of a 65-byte message packed into 17 big-endian words.
+pub fn sha256_65(m: [u32; 17]) -> [u32; 8] {
+    let mut block = [0u32; 16];
+    block.copy_from_slice(&m[..16]);
+    let state = sha256_compress(SHA256_IV, block);
+    let mut block = [0u32; 16];
+    block[0] = (m[16] & 0xff00_0000) | 0x0080_0000;
+    block[15] = 65 * 8;
+    sha256_compress(state, block)
+}
+
+fn rmd_f(j: usize, x: u32, y: u32, z: u32) -> u32 {
+    match j / 16 {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+/// RIPEMD-160 of a SHA-256 digest given as big-endian words. The result is
+/// five little-endian words whose bytes are the digest.
+pub fn ripemd160_32(d: [u32; 8]) -> [u32; 5] {
+    let mut x = [0u32; 16];
+    for i in 0..8 {
+        x[i] = d[i].swap_bytes();
+    }
+    x[8] = 0x80;
+    x[14] = 256;
+
+    let h = [
+        0x67452301u32,
+        0xefcdab89,
+        0x98badcfe,
+        0x10325476,
+        0xc3d2e1f0,
+    ];
+    let [mut al, mut bl, mut cl, mut dl, mut el] = h;
+    let [mut ar, mut br, mut cr, mut dr, mut er] = h;
+    for j in 0..80 {
+        let t = al
+            .wrapping_add(rmd_f(j, bl, cl, dl))
+            .wrapping_add(x[RMD_R[j]])
+            .wrapping_add(RMD_K[j / 16])
+            .rotate_left(RMD_S[j])
+            .wrapping_add(el);
+        al = el;
+        el = dl;
+        dl = cl.rotate_left(10);
+        cl = bl;
+        bl = t;
+
+        let t = ar
+            .wrapping_add(rmd_f(79 - j, br, cr, dr))
+            .wrapping_add(x[RMD_RP[j]])
+            .wrapping_add(RMD_KP[j / 16])
+            .rotate_left(RMD_SP[j])
+            .wrapping_add(er);
+        ar = er;
+        er = dr;
+        dr = cr.rotate_left(10);
+        cr = br;
+        br = t;
+    }
+    [
+        h[1].wrapping_add(cl).wrapping_add(dr),
+        h[2].wrapping_add(dl).wrapping_add(er),
+        h[3].wrapping_add(el).wrapping_add(ar),
+        h[4].wrapping_add(al).wrapping_add(br),
+        h[0].wrapping_add(bl).wrapping_add(cr),
+    ]
+}
+
+/// Big-endian bytes of SHA-256 state words.
+pub fn sha256_bytes(words: [u32; 8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for (o, w) in out.chunks_exact_mut(4).zip(words) {
+        o.copy_from_slice(&w.to_be_bytes());
+    }
+    out
+}
+
+/// Little-endian bytes of RIPEMD-160 state words.
+pub fn ripemd160_bytes(words: [u32; 5]) -> [u8; 20] {
+    let mut out = [0u8; 20];
+    for (o, w) in out.chunks_exact_mut(4).zip(words) {
+        o.copy_from_slice(&w.to_le_bytes());
+    }
+    out
+}
+
+/// Known answers: (message hex, SHA-256 hex, HASH160 hex).
+pub const VECTORS: [(&str, &str, &str); 6] = [
+    // P2WPKH redeem scripts, hashed for P2SH-P2WPKH.
+    (
+        "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+        "8838f796bf4970b148779c05b74b8c49515b322d04035f7faa5d9b2375df2396",
+        "bcfeb728b584253d5f3f70bcb780e9ef218a68f4",
+    ),
+    (
+        "0014ffffffffffffffffffffffffffffffffffffffff",
+        "8708c808a49b0d2d7add5fc9b9ba0b3efae5e431d5099b8287dbaca7fa1b483c",
+        "26726fd81dde21e6cf2ee47208f207b6038e7629",
+    ),
+    (
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        "0f715baf5d4c2ed329785cef29e562f73488c8a2bb9dbc5700b361d54b9b0554",
+        "751e76e8199196d454941c45d1b3a323f1433bd6",
+    ),
+    (
+        "03ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "393363907768ca818617437791eeca0978c26a33c85a0ba2596ba50a72db6535",
+        "be584670ea13a11171ca218b60e0c66d0e54fcae",
+    ),
+    (
+        "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+         483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0",
+        "91b24bf9f5288532960ac687abb035127b1d28a5",
+    ),
+    (
+        "0400000000000000000000000000000000000000000000000000000000000000\
+         000000000000000000000000000000000000000000000000000000000000000000",
+        "59ef1a5a00f35b1a722da56ca70b52a721f33998634d4fa4259301f170f7b6bd",
+        "3b4df4363caa9e3bd9da58020d3080be8230a4ae",
+    ),
+];
+
+/// Model SHA-256 and HASH160 of a 22-, 33- or 65-byte message.
+pub fn model_hashes(msg: &[u8]) -> ([u8; 32], [u8; 20]) {
+    let words = pack_be(msg);
+    let sha = match msg.len() {
+        22 => sha256_22(words.try_into().expect("6 words")),
+        33 => sha256_33(words.try_into().expect("9 words")),
+        65 => sha256_65(words.try_into().expect("17 words")),
+        n => panic!("unsupported message length {n}"),
+    };
+    (sha256_bytes(sha), ripemd160_bytes(ripemd160_32(sha)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::hash160;
+    use crate::gpu::request_device;
+    use pollster::block_on;
+    use serial_test::file_serial;
+    use sha2::{Digest, Sha256};
+    use wgpu::util::DeviceExt;
+
+    #[test]
+    fn model_matches_known_answers() {
+        for (msg, sha, h160) in VECTORS {
+            let msg = hex::decode(msg).unwrap();
+            let (s, h) = model_hashes(&msg);
+            assert_eq!(hex::encode(s), sha);
+            assert_eq!(hex::encode(h), h160);
+        }
+    }
+
+    #[test]
+    fn model_matches_crates_on_varied_messages() {
+        for len in [22usize, 33, 65] {
+            for seed in 0u8..64 {
+                let msg: Vec<u8> = (0..len)
+                    .map(|i| {
+                        (i as u8)
+                            .wrapping_mul(31)
+                            .wrapping_add(seed.wrapping_mul(97))
+                    })
+                    .collect();
+                let (s, h) = model_hashes(&msg);
+                assert_eq!(s[..], Sha256::digest(&msg)[..]);
+                assert_eq!(h, hash160(&msg));
+            }
+        }
+    }
+
+    /// Runs `shaders/hash_kat.wgsl` on [`VECTORS`].
+    #[test]
+    #[file_serial(gpu)]
+    #[ignore]
+    fn wgsl_hashes_match_known_answers() {
+        const KAT_WGSL: &str = concat!(
+            include_str!("../shaders/secp256k1.wgsl"),
+            "\n",
+            include_str!("../shaders/hash.wgsl"),
+            "\n",
+            include_str!("../shaders/hash_kat.wgsl")
+        );
+        let (device, queue) = block_on(request_device()).expect("gpu init");
+
+        // 18 words per message: length, then 17 packed words.
+        let mut input = Vec::new();
+        for (msg, _, _) in VECTORS {
+            let msg = hex::decode(msg).unwrap();
+            let mut words = pack_be(&msg);
+            words.resize(17, 0);
+            input.push(msg.len() as u32);
+            input.extend(words);
+        }
+        let out_size = (VECTORS.len() * 13 * 4) as u64;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("hash_kat.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(KAT_WGSL.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("hash kat"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+        });
+        let msgs = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("kat msgs"),
+            contents: bytemuck::cast_slice(&input),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let out = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("kat out"),
+            size: out_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("kat readback"),
+            size: out_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("kat bind group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: msgs.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: out.as_entire_binding(),
+                },
+            ],
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut cpass = encoder.begin_compute_pass(&Default::default());
+            cpass.set_pipeline(&pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            cpass.dispatch_workgroups(1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&out, 0, &readback, 0, out_size);
+        queue.submit(Some(encoder.finish()));
+        readback.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+
+        let data = readback.slice(..).get_mapped_range();
+        let words: &[u32] = bytemuck::cast_slice(&data);
+        for (rec, (_, sha, h160)) in words.chunks_exact(13).zip(VECTORS) {
+            let s = sha256_bytes(rec[..8].try_into().unwrap());
+            let h = ripemd160_bytes(rec[8..].try_into().unwrap());
+            assert_eq!(hex::encode(s), sha);
+            assert_eq!(hex::encode(h), h160);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Search a private-key range for Bitcoin addresses, generating
+//! candidates on the GPU and verifying them on the CPU.
+//!
+//! ```no_run
+//! use gpu_bitcrack::{SearchConfig, SearchEngine, Target};
+//!
+//! # async fn demo() -> anyhow::Result<()> {
+//! let config = SearchConfig::new(
+//!     "4:7".parse()?,
+//!     "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA".parse::<Target>()?,
+//! );
+//! let mut engine = SearchEngine::new(config).await?;
+//! for hit in engine.run().await?.hits() {
+//!     println!("{} {}", hit.address, hit.private_key_hex());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod address;
+pub mod backend;
+pub mod chain;
+pub mod checkpoint;
+pub mod cpu;
+pub mod descriptor;
+pub mod gpu;
+pub mod gpu_ec;
+#[cfg(test)]
+mod hash_model;
+pub mod network;
+pub mod progress;
+pub mod search;
+pub mod target;
+pub mod u256;
+
+pub use backend::{BatchFormat, CandidateBackend};
+pub use chain::{Chain, Chains};
+pub use cpu::CpuSeq;
+pub use gpu::GpuSeq;
+pub use gpu_ec::GpuEc;
+pub use network::Network;
+pub use search::{
+    Backend, CheckpointConfig, Hit, Keyspace, PubkeyFormat, SearchConfig, SearchEngine,
+    SearchOutcome, verify_batch,
+};
+pub use target::{AddressKind, Target, TargetSet};
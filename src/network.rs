@@ -0,0 +1,109 @@
+//! Bitcoin networks and the chain prefixes their addresses and keys use.
+
+use crate::chain::Chain;
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::str::FromStr;
+
+/// A Bitcoin network. Testnet, signet and regtest share their Base58
+/// prefixes, and testnet and signet share their bech32 HRP, so an address
+/// alone cannot always tell them apart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, clap::ValueEnum)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    /// Every network, in the order [`Network::of_address`] prefers them.
+    pub const ALL: [Self; 4] = [Self::Mainnet, Self::Testnet, Self::Signet, Self::Regtest];
+
+    /// The prefixes this network's addresses and keys use.
+    pub fn chain(self) -> Chain {
+        match self {
+            Self::Mainnet => Chain::BITCOIN,
+            Self::Testnet | Self::Signet => Chain::BITCOIN_TESTNET,
+            Self::Regtest => Chain::BITCOIN_REGTEST,
+        }
+    }
+
+    /// The first network whose prefixes `addr` uses: the bech32 HRP of a
+    /// single-case address, or the version byte of a Base58Check address.
+    pub fn of_address(addr: &str) -> Option<Self> {
+        let lower = addr.to_ascii_lowercase();
+        let one_case = addr == lower || addr == addr.to_ascii_uppercase();
+        if let Some(network) = Self::ALL.into_iter().find(|n| {
+            one_case
+                && n.chain()
+                    .hrp
+                    .is_some_and(|hrp| lower.starts_with(&format!("{hrp}1")))
+        }) {
+            return Some(network);
+        }
+        let version = *bs58::decode(addr).into_vec().ok()?.first()?;
+        Self::ALL.into_iter().find(|n| {
+            let chain = n.chain();
+            version == chain.p2pkh_version || version == chain.p2sh_version
+        })
+    }
+}
+
+impl fmt::Display for Network {
+    /// The name used on the command line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Mainnet => "mainnet",
+            Self::Testnet => "testnet",
+            Self::Signet => "signet",
+            Self::Regtest => "regtest",
+        })
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        <Self as clap::ValueEnum>::from_str(s, false).map_err(|_| anyhow!("unknown network {s:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_the_network_from_address_prefixes() {
+        for (addr, network) in [
+            ("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", Network::Mainnet),
+            ("3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN", Network::Mainnet),
+            (
+                "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+                Network::Mainnet,
+            ),
+            ("mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r", Network::Testnet),
+            ("2NAUYAHhujozruyzpsFRP63mbrdaU5wnEpN", Network::Testnet),
+            (
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+                Network::Testnet,
+            ),
+            (
+                "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+                Network::Regtest,
+            ),
+        ] {
+            assert_eq!(Network::of_address(addr), Some(network), "{addr}");
+        }
+        assert_eq!(Network::of_address("not an address"), None);
+        // Bech32 is never mixed-case; this can only be Base58.
+        assert_eq!(
+            Network::of_address("bc1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"),
+            None
+        );
+        assert_eq!("signet".parse::<Network>().unwrap(), Network::Signet);
+        assert_eq!(Network::Signet.chain(), Network::Testnet.chain());
+    }
+}
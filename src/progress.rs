@@ -0,0 +1,190 @@
+//! Periodic progress lines for a running search.
+
+use crate::search::Keyspace;
+use crate::u256::U256;
+use std::time::{Duration, Instant};
+
+/// Default time between progress lines.
+pub const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Turns the verified-key cursor of a search into progress lines.
+///
+/// Percent complete counts from the start of the whole keyspace, so a resumed
+/// search picks up where it left off; rates and ETA only count keys verified
+/// since this reporter was created.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    keyspace: Keyspace,
+    interval: Duration,
+    started: Instant,
+    /// Keys already verified when this session started.
+    base: U256,
+    /// Time and verified count of the previous line.
+    last: (Instant, U256),
+}
+
+impl Progress {
+    /// Reporter for a search of `keyspace` whose first unverified key is
+    /// `first`, emitting at most one line per `interval`.
+    pub fn new(keyspace: Keyspace, first: U256, interval: Duration) -> Self {
+        Self::new_at(keyspace, first, interval, Instant::now())
+    }
+
+    fn new_at(keyspace: Keyspace, first: U256, interval: Duration, now: Instant) -> Self {
+        let base = first - keyspace.start();
+        Self {
+            keyspace,
+            interval,
+            started: now,
+            base,
+            last: (now, base),
+        }
+    }
+
+    /// A progress line if `interval` has passed since the previous one, given
+    /// that every key through `last_verified` has been checked.
+    pub fn report(&mut self, last_verified: U256) -> Option<String> {
+        self.report_at(last_verified, Instant::now())
+    }
+
+    fn report_at(&mut self, last_verified: U256, now: Instant) -> Option<String> {
+        if now.duration_since(self.last.0) < self.interval {
+            return None;
+        }
+        // Counts are kept modulo 2^256: only a fully searched 0..=MAX keyspace
+        // would wrap, and that cannot happen.
+        let checked = (last_verified - self.keyspace.start()).wrapping_add(U256::ONE);
+        let checked_f = (last_verified - self.keyspace.start()).to_f64() + 1.0;
+        let total = (self.keyspace.end() - self.keyspace.start()).to_f64() + 1.0;
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        let since_last = now.duration_since(self.last.0).as_secs_f64();
+
+        let average = rate(checked.wrapping_sub(self.base).to_f64(), elapsed);
+        let current = rate(checked.wrapping_sub(self.last.1).to_f64(), since_last);
+        let eta = if average > 0.0 {
+            format_duration((total - checked_f) / average)
+        } else {
+            "unknown".to_owned()
+        };
+        self.last = (now, checked);
+
+        Some(format!(
+            "[{}] key {last_verified:#x} | {}keys | {:.2}% | {} (avg {}) | ETA {eta}",
+            format_duration(elapsed),
+            si(checked_f),
+            checked_f / total * 100.0,
+            keys_per_sec(current),
+            keys_per_sec(average),
+        ))
+    }
+}
+
+fn rate(keys: f64, secs: f64) -> f64 {
+    if secs > 0.0 { keys / secs } else { 0.0 }
+}
+
+fn keys_per_sec(rate: f64) -> String {
+    format!("{}keys/s", si(rate))
+}
+
+/// `value` with an SI prefix and a trailing space or prefix letter, ready to
+/// be followed by a unit: `12.35 M`, `250 `.
+fn si(value: f64) -> String {
+    const UNITS: [&str; 7] = ["", "K", "M", "G", "T", "P", "E"];
+    let mut v = value;
+    let mut unit = 0;
+    while v >= 1000.0 && unit < UNITS.len() - 1 {
+        v /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{v:.0} ")
+    } else if v >= 1000.0 {
+        format!("{value:.3e} ")
+    } else {
+        format!("{v:.2} {}", UNITS[unit])
+    }
+}
+
+/// `secs` as `1d 02:03:04`, or in years once that stops being readable.
+fn format_duration(secs: f64) -> String {
+    const YEAR: f64 = 365.25 * 86_400.0;
+    if secs >= 100.0 * YEAR {
+        return format!("{:.3e} years", secs / YEAR);
+    }
+    let s = secs.round() as u64;
+    let (days, h, m, s) = (s / 86_400, s / 3600 % 24, s / 60 % 60, s % 60);
+    if days > 0 {
+        format!("{days}d {h:02}:{m:02}:{s:02}")
+    } else {
+        format!("{h:02}:{m:02}:{s:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_counts_percent_rates_and_eta() {
+        let ks: Keyspace = "1:2710".parse().unwrap(); // 10_000 keys
+        let t0 = Instant::now();
+        let mut p = Progress::new_at(ks, ks.start(), Duration::from_secs(5), t0);
+
+        // Too early: nothing to report.
+        assert_eq!(
+            p.report_at(U256::from_u64(100), t0 + Duration::from_secs(1)),
+            None
+        );
+
+        let line = p
+            .report_at(U256::from_u64(2500), t0 + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            line,
+            "[00:00:10] key 0x9c4 | 2.50 Kkeys | 25.00% | 250 keys/s (avg 250 keys/s) | ETA 00:00:30"
+        );
+
+        let line = p
+            .report_at(U256::from_u64(5000), t0 + Duration::from_secs(15))
+            .unwrap();
+        assert!(
+            line.contains("| 50.00% | 500 keys/s (avg 333 keys/s) |"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn resumed_search_counts_percent_from_keyspace_start_but_rates_from_resume() {
+        let ks: Keyspace = "1:2710".parse().unwrap();
+        let t0 = Instant::now();
+        let mut p = Progress::new_at(ks, U256::from_u64(5001), Duration::ZERO, t0);
+        let line = p
+            .report_at(U256::from_u64(6000), t0 + Duration::from_secs(10))
+            .unwrap();
+        assert!(
+            line.contains("| 60.00% | 100 keys/s (avg 100 keys/s) | ETA 00:00:40"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn formats_huge_keyspaces() {
+        let ks = Keyspace::new(U256::ZERO, U256::MAX).unwrap();
+        let t0 = Instant::now();
+        let mut p = Progress::new_at(ks, U256::ZERO, Duration::ZERO, t0);
+        let line = p
+            .report_at(U256::from_u64(999_999), t0 + Duration::from_secs(1))
+            .unwrap();
+        assert!(line.contains("1.00 Mkeys | 0.00% | 1.00 Mkeys/s"), "{line}");
+        assert!(line.ends_with("years"), "{line}");
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(0.0), "00:00:00");
+        assert_eq!(format_duration(3725.0), "01:02:05");
+        assert_eq!(format_duration(90_061.0), "1d 01:01:01");
+        assert_eq!(si(1_234_567.0), "1.23 M");
+    }
+}
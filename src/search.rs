@@ -0,0 +1,1525 @@
+//! Keyspace search: the public entry point tying GPU generation to CPU
+//! verification.
+
+use crate::address::{
+    eth_address_from_pubkey, hash160, nsec_from_secret, p2wpkh_script, taproot_output_key,
+    wif_from_secret,
+};
+use crate::backend::{BatchFormat, CandidateBackend, SLOTS};
+use crate::checkpoint::Checkpoint;
+use crate::cpu::CpuSeq;
+use crate::gpu_ec::GpuEc;
+use crate::progress::Progress;
+use crate::target::{Target, TargetSet, serialize_point};
+use crate::u256::U256;
+use anyhow::{Result, anyhow};
+use hex::ToHex;
+use rayon::prelude::*;
+use secp256k1::{PublicKey, Secp256k1, SecretKey, XOnlyPublicKey};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Default number of candidates per GPU dispatch.
+pub const DEFAULT_BATCH: u32 = 1_000_000;
+
+/// Inclusive range of private keys to search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keyspace {
+    start: U256,
+    end: U256,
+}
+
+impl Keyspace {
+    /// Build a keyspace; fails if `start > end`.
+    pub fn new(start: U256, end: U256) -> Result<Self> {
+        if start > end {
+            return Err(anyhow!("keyspace start > end"));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// First key in the range.
+    pub fn start(&self) -> U256 {
+        self.start
+    }
+
+    /// Last key in the range (inclusive).
+    pub fn end(&self) -> U256 {
+        self.end
+    }
+}
+
+impl fmt::Display for Keyspace {
+    /// `START:END` in hex, the form [`Keyspace::from_str`] reads.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}:{:x}", self.start, self.end)
+    }
+}
+
+impl FromStr for Keyspace {
+    type Err = anyhow::Error;
+
+    /// Parse `START:END` in hex, e.g. `1000000:1ffffff`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (start_str, end_str) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("--keyspace must be START:END hex"))?;
+        let start = U256::from_hex(start_str)?;
+        let end = U256::from_hex(end_str)?;
+        Self::new(start, end)
+    }
+}
+
+/// Which device generates candidate keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Backend {
+    /// Use the GPU if an adapter is available, otherwise the CPU.
+    #[default]
+    Auto,
+    /// Require a GPU adapter; keys are derived, hashed and compared with the
+    /// target on the GPU.
+    Gpu,
+    /// Generate candidates on rayon threads; no GPU needed.
+    Cpu,
+}
+
+/// Which SEC1 serializations of each candidate's public key are hashed and
+/// compared with the targets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PubkeyFormat {
+    /// 33-byte keys, as used by wallets since 2012.
+    #[default]
+    Compressed,
+    /// 65-byte keys, as used by early wallets.
+    Uncompressed,
+    /// Both; costs an extra hash per key.
+    Both,
+}
+
+impl PubkeyFormat {
+    /// Whether compressed keys are hashed.
+    pub fn compressed(self) -> bool {
+        self != Self::Uncompressed
+    }
+
+    /// Whether uncompressed keys are hashed.
+    pub fn uncompressed(self) -> bool {
+        self != Self::Compressed
+    }
+}
+
+impl fmt::Display for PubkeyFormat {
+    /// The name used on the command line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Compressed => "compressed",
+            Self::Uncompressed => "uncompressed",
+            Self::Both => "both",
+        })
+    }
+}
+
+impl FromStr for PubkeyFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        <Self as clap::ValueEnum>::from_str(s, false)
+            .map_err(|_| anyhow!("unknown public key format {s:?}"))
+    }
+}
+
+/// Default time between checkpoint saves.
+pub const DEFAULT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where and how often a search records its progress.
+#[derive(Clone, Debug)]
+pub struct CheckpointConfig {
+    pub path: PathBuf,
+    /// Minimum time between saves; the final state is always saved.
+    pub interval: Duration,
+    /// Continue from the checkpoint already at `path` instead of the start
+    /// of the keyspace.
+    pub resume: bool,
+}
+
+impl CheckpointConfig {
+    /// Save to `path` every [`DEFAULT_CHECKPOINT_INTERVAL`], without resuming.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            interval: DEFAULT_CHECKPOINT_INTERVAL,
+            resume: false,
+        }
+    }
+}
+
+/// Everything needed to run a search.
+#[derive(Clone, Debug)]
+pub struct SearchConfig {
+    pub keyspace: Keyspace,
+    /// The search ends once every one of these is found.
+    pub targets: TargetSet,
+    pub pubkeys: PubkeyFormat,
+    /// End the search once this many hits are reported, even if targets are
+    /// still missing. The batch holding the last of them is reported in full.
+    pub stop_after: Option<NonZeroUsize>,
+    /// Candidates per GPU dispatch (or per CPU batch).
+    pub batch: u32,
+    pub backend: Backend,
+    pub checkpoint: Option<CheckpointConfig>,
+    /// Print a progress line to stderr at most this often; `None` is silent.
+    pub progress: Option<Duration>,
+}
+
+impl SearchConfig {
+    /// Config with the default batch size.
+    pub fn new(keyspace: Keyspace, targets: impl Into<TargetSet>) -> Self {
+        Self {
+            keyspace,
+            targets: targets.into(),
+            pubkeys: PubkeyFormat::Compressed,
+            stop_after: None,
+            batch: DEFAULT_BATCH,
+            backend: Backend::Auto,
+            checkpoint: None,
+            progress: None,
+        }
+    }
+}
+
+/// A private key that matched a target.
+#[derive(Clone, Debug)]
+pub struct Hit {
+    /// The target this key matched.
+    pub target: Target,
+    pub secret_key: SecretKey,
+    /// Whether the compressed or the uncompressed public key matched.
+    pub compressed: bool,
+    /// SEC1 public key in the form that matched: 33 or 65 bytes.
+    pub public_key: Vec<u8>,
+    pub address: String,
+    /// WIF for the matching form; only the compressed one carries the
+    /// `0x01` suffix. `None` for Ethereum targets, which take the raw key.
+    pub wif: Option<String>,
+    /// NIP-19 `nsec…` encoding of the key, for a Nostr target.
+    pub nsec: Option<String>,
+    /// Output descriptor of the target's script with [`Hit::wif`] as its
+    /// key, checksum included.
+    pub descriptor: Option<String>,
+    /// The P2WPKH redeem script, for a P2SH-P2WPKH target.
+    pub redeem_script: Option<Vec<u8>>,
+}
+
+impl Hit {
+    fn new(sk: SecretKey, public_key: Vec<u8>, compressed: bool, target: Target) -> Self {
+        let redeem_script = target
+            .is_nested_segwit()
+            .then(|| p2wpkh_script(&hash160(&public_key)).to_vec());
+        let wif = target
+            .chain()
+            .map(|chain| wif_from_secret(&sk, compressed, chain));
+        Self {
+            target,
+            secret_key: sk,
+            compressed,
+            public_key,
+            address: target.address(),
+            nsec: matches!(target, Target::Nostr { .. }).then(|| nsec_from_secret(&sk)),
+            descriptor: wif.as_deref().and_then(|wif| target.descriptor(wif)),
+            wif,
+            redeem_script,
+        }
+    }
+
+    /// Hits for `sk` on `targets`, one per target matched by a form in
+    /// `pubkeys`.
+    fn find(
+        secp: &Secp256k1<secp256k1::All>,
+        sk: SecretKey,
+        pubkeys: PubkeyFormat,
+        targets: &TargetSet,
+    ) -> Vec<Self> {
+        let pk = PublicKey::from_secret_key(secp, &sk);
+        let mut hits = Vec::new();
+        let mut check = |public_key: &[u8], compressed: bool| {
+            for target in targets.paid_by(&hash160(public_key), compressed) {
+                hits.push(Self::new(sk, public_key.to_vec(), compressed, *target));
+            }
+        };
+        if pubkeys.compressed() {
+            check(&pk.serialize(), true);
+        }
+        if pubkeys.uncompressed() {
+            check(&pk.serialize_uncompressed(), false);
+        }
+        // Taproot commits to the x-only key, which only the compressed form
+        // carries.
+        if pubkeys.compressed() && targets.has_taproot() {
+            let output_key = taproot_output_key(secp, &pk.x_only_public_key().0);
+            for target in targets.taproot(&output_key) {
+                hits.push(Self::new(sk, pk.serialize().to_vec(), true, *target));
+            }
+        }
+        // Ethereum hashes the uncompressed key whatever `pubkeys` selects.
+        if targets.has_ethereum() {
+            let public_key = pk.serialize_uncompressed();
+            if let Some(target) = targets.ethereum(&eth_address_from_pubkey(&public_key)) {
+                hits.push(Self::new(sk, public_key.to_vec(), false, *target));
+            }
+        }
+        // Nostr compares the x coordinate itself, also whatever `pubkeys`
+        // selects.
+        if targets.has_nostr() {
+            let public_key = pk.serialize();
+            let x: &[u8; 32] = public_key[1..].try_into().expect("32 bytes");
+            if let Some(target) = targets.nostr(x) {
+                hits.push(Self::new(sk, public_key.to_vec(), true, *target));
+            }
+        }
+        // Public keys are compared as points, in either form.
+        if targets.has_pubkey() {
+            for target in targets.pubkey(&pk.serialize()) {
+                let Target::PubKey {
+                    key, compressed, ..
+                } = target
+                else {
+                    unreachable!("TargetSet::pubkey only returns public key targets");
+                };
+                let public_key = serialize_point(key, *compressed);
+                hits.push(Self::new(sk, public_key, *compressed, *target));
+            }
+        }
+        hits
+    }
+
+    /// [`PubkeyFormat::Compressed`] or [`PubkeyFormat::Uncompressed`].
+    pub fn form(&self) -> PubkeyFormat {
+        if self.compressed {
+            PubkeyFormat::Compressed
+        } else {
+            PubkeyFormat::Uncompressed
+        }
+    }
+
+    /// Big-endian hex of the private key.
+    pub fn private_key_hex(&self) -> String {
+        self.secret_key.secret_bytes().encode_hex::<String>()
+    }
+}
+
+/// Result of [`SearchEngine::run`]. Hits are listed in ascending key order.
+#[derive(Clone, Debug)]
+pub enum SearchOutcome {
+    /// Every target was found, or [`SearchConfig::stop_after`] hits were.
+    Found(Vec<Hit>),
+    /// The keyspace was searched to the end; the hits are those of the
+    /// targets that were found, if any.
+    Exhausted(Vec<Hit>),
+    /// Stopped through [`SearchEngine::stop_flag`]. Every key from the start
+    /// of the keyspace through `last_verified` was checked.
+    Interrupted { last_verified: U256, hits: Vec<Hit> },
+}
+
+impl SearchOutcome {
+    /// Hits found before the search ended.
+    pub fn hits(&self) -> &[Hit] {
+        match self {
+            Self::Found(hits) | Self::Exhausted(hits) | Self::Interrupted { hits, .. } => hits,
+        }
+    }
+}
+
+/// Runs a [`SearchConfig`], generating candidates on the selected backend and
+/// verifying each batch on the CPU.
+pub struct SearchEngine {
+    config: SearchConfig,
+    backend: Box<dyn CandidateBackend>,
+    kind: Backend,
+    secp: Secp256k1<secp256k1::All>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SearchEngine {
+    /// Initialise the backend for `config`.
+    ///
+    /// With [`Backend::Auto`], a failure to initialise the GPU is reported on
+    /// stderr and the CPU backend is used instead. A batch larger than the
+    /// backend accepts is clamped with a warning.
+    pub async fn new(config: SearchConfig) -> Result<Self> {
+        let (backend, kind): (Box<dyn CandidateBackend>, _) = match config.backend {
+            Backend::Gpu => (Box::new(gpu_backend(&config).await?), Backend::Gpu),
+            Backend::Cpu => (Box::new(CpuSeq::new()), Backend::Cpu),
+            Backend::Auto => match gpu_backend(&config).await {
+                Ok(gpu) => (Box::new(gpu), Backend::Gpu),
+                Err(e) => {
+                    eprintln!("{e}; falling back to the CPU backend");
+                    (Box::new(CpuSeq::new()), Backend::Cpu)
+                }
+            },
+        };
+        Ok(Self {
+            config: clamp_batch(config, backend.as_ref()),
+            backend,
+            kind,
+            secp: Secp256k1::new(),
+            stop: Arc::default(),
+        })
+    }
+
+    /// Run `config` on a caller-supplied backend. [`SearchConfig::backend`]
+    /// is ignored and [`SearchEngine::backend`] reports [`Backend::Auto`].
+    pub fn with_backend(config: SearchConfig, backend: Box<dyn CandidateBackend>) -> Self {
+        Self {
+            config: clamp_batch(config, backend.as_ref()),
+            backend,
+            kind: Backend::Auto,
+            secp: Secp256k1::new(),
+            stop: Arc::default(),
+        }
+    }
+
+    /// The configuration this engine was built with.
+    pub fn config(&self) -> &SearchConfig {
+        &self.config
+    }
+
+    /// The built-in backend in use, or [`Backend::Auto`] for one supplied
+    /// through [`SearchEngine::with_backend`].
+    pub fn backend(&self) -> Backend {
+        self.kind
+    }
+
+    /// Flag that asks a running search to stop. Once set, no new batches are
+    /// submitted; those already in flight are finished and verified, and
+    /// [`SearchEngine::run`] returns [`SearchOutcome::Interrupted`].
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Search the keyspace until every target is found, the
+    /// [`SearchConfig::stop_after`] limit is reached or the keyspace is
+    /// exhausted.
+    ///
+    /// With [`SearchConfig::checkpoint`] set, progress is saved as batches are
+    /// verified and always when the search ends, however it ends, and a resumed
+    /// search first checks that the saved keyspace, targets and batch match
+    /// this config. The hits saved with it are reported to `on_hit` first and
+    /// count towards the targets found and [`SearchConfig::stop_after`].
+    pub async fn run(&mut self) -> Result<SearchOutcome> {
+        self.run_with(&mut |_| {}).await
+    }
+
+    /// Like [`SearchEngine::run`], calling `on_hit` with each hit as soon as
+    /// its batch is verified, in ascending key order.
+    pub async fn run_with(&mut self, on_hit: &mut dyn FnMut(&Hit)) -> Result<SearchOutcome> {
+        let config = &self.config;
+        if config.targets.is_empty() {
+            return Err(anyhow!("no targets to search for"));
+        }
+        let batch = config.batch.max(1);
+        let digest = config.targets.digest();
+        let mut keyspace = config.keyspace;
+        let mut resumed = Vec::new();
+        if let Some(cp) = config.checkpoint.as_ref().filter(|cp| cp.resume) {
+            let saved = Checkpoint::load(&cp.path)?;
+            saved.ensure_matches(&config.keyspace, &digest, config.pubkeys, batch)?;
+            resumed = saved_hits(&saved, &self.secp, &config.targets, config.pubkeys)?;
+            resumed.iter().for_each(&mut *on_hit);
+            let stop_after = config.stop_after.map_or(usize::MAX, NonZeroUsize::get);
+            let all_found = config
+                .targets
+                .iter()
+                .all(|t| resumed.iter().any(|hit| hit.target == *t));
+            if all_found || resumed.len() >= stop_after {
+                return Ok(SearchOutcome::Found(resumed));
+            }
+            match saved.remaining() {
+                Some(rest) => keyspace = rest,
+                None => return Ok(SearchOutcome::Exhausted(resumed)),
+            }
+        }
+
+        let mut found: Vec<_> = resumed.iter().map(saved_hit).collect();
+        let save = |last_verified: U256, found: &[(U256, String)]| -> Result<()> {
+            match &config.checkpoint {
+                Some(cp) => Checkpoint {
+                    keyspace: config.keyspace,
+                    targets_digest: digest,
+                    pubkeys: config.pubkeys,
+                    batch,
+                    last_verified,
+                    found: found.to_vec(),
+                }
+                .save(&cp.path),
+                None => Ok(()),
+            }
+        };
+        let mut progress = config
+            .progress
+            .map(|interval| Progress::new(config.keyspace, keyspace.start(), interval));
+        let mut last_save = Instant::now();
+        let mut verified = None;
+        let mut on_verified = |last_verified: U256, hits: &[Hit]| -> Result<()> {
+            hits.iter().for_each(&mut *on_hit);
+            found.extend(hits.iter().map(saved_hit));
+            if let Some(line) = progress.as_mut().and_then(|p| p.report(last_verified)) {
+                eprintln!("{line}");
+            }
+            verified = Some(last_verified);
+            let Some(cp) = &config.checkpoint else {
+                return Ok(());
+            };
+            if last_save.elapsed() >= cp.interval {
+                save(last_verified, &found)?;
+                last_save = Instant::now();
+            }
+            Ok(())
+        };
+        let outcome = run_pipeline(
+            self.backend.as_mut(),
+            &self.secp,
+            config,
+            &keyspace,
+            resumed,
+            &self.stop,
+            &mut on_verified,
+        )?;
+        if let Some(last_verified) = verified {
+            save(last_verified, &found)?;
+        }
+        Ok(outcome)
+    }
+}
+
+/// How `hit` is recorded in a [`Checkpoint`]: its private key and target.
+fn saved_hit(hit: &Hit) -> (U256, String) {
+    (
+        U256::from_be_bytes(hit.secret_key.secret_bytes()),
+        hit.target.to_string(),
+    )
+}
+
+/// The hits recorded in `saved`, re-derived from their private keys. Fails
+/// if a saved key does not match its target.
+fn saved_hits(
+    saved: &Checkpoint,
+    secp: &Secp256k1<secp256k1::All>,
+    targets: &TargetSet,
+    pubkeys: PubkeyFormat,
+) -> Result<Vec<Hit>> {
+    saved
+        .found
+        .iter()
+        .map(|(key, target)| {
+            SecretKey::from_slice(&key.to_be_bytes())
+                .ok()
+                .into_iter()
+                .flat_map(|sk| Hit::find(secp, sk, pubkeys, targets))
+                .find(|hit| hit.target.to_string() == *target)
+                .ok_or_else(|| anyhow!("checkpoint key {key:x} does not match {target}"))
+        })
+        .collect()
+}
+
+/// The GPU backend for `config`. It matches on the GPU unless some targets
+/// are not HASH160s, which the shader cannot match; then it returns
+/// compressed public keys for the CPU to check.
+async fn gpu_backend(config: &SearchConfig) -> Result<GpuEc> {
+    let batch = config.batch.max(1);
+    if !config.targets.hash160_only() {
+        GpuEc::new(batch, BatchFormat::CompressedPubKeys).await
+    } else {
+        GpuEc::with_targets(batch, &config.targets, config.pubkeys).await
+    }
+}
+
+/// Lower `config.batch` to what `backend` accepts, warning on stderr.
+fn clamp_batch(mut config: SearchConfig, backend: &dyn CandidateBackend) -> SearchConfig {
+    let max = backend.max_batch();
+    if config.batch > max {
+        eprintln!(
+            "--batch {} exceeds what the device can hold; using {max}",
+            config.batch
+        );
+        config.batch = max;
+    }
+    config
+}
+
+/// Double-buffered search of `keyspace` (all or the rest of
+/// `config.keyspace`): batch `i + 1` is generated while batch `i` is
+/// verified. `on_verified` is called with the last key of each verified
+/// batch and every hit it held, sorted by key. The search ends once every
+/// target is found or `config.stop_after` hits are, counting the `resumed`
+/// hits of a checkpoint.
+///
+/// Once `stop` is set no further batch is submitted; the one in flight is
+/// still waited on and verified before returning
+/// [`SearchOutcome::Interrupted`].
+fn run_pipeline(
+    backend: &mut dyn CandidateBackend,
+    secp: &Secp256k1<secp256k1::All>,
+    config: &SearchConfig,
+    keyspace: &Keyspace,
+    resumed: Vec<Hit>,
+    stop: &AtomicBool,
+    on_verified: &mut dyn FnMut(U256, &[Hit]) -> Result<()>,
+) -> Result<SearchOutcome> {
+    let (targets, pubkeys) = (&config.targets, config.pubkeys);
+    let batch = config.batch.max(1);
+    let stop_after = config.stop_after.map_or(usize::MAX, NonZeroUsize::get);
+    let format = backend.format();
+    if format == BatchFormat::Hash160 && pubkeys.uncompressed() {
+        return Err(anyhow!(
+            "{format:?} batches only cover compressed public keys"
+        ));
+    }
+    if format == BatchFormat::Hash160 && !targets.hash160_only() {
+        return Err(anyhow!("{format:?} batches only match HASH160 targets"));
+    }
+    let mut missing = targets.clone();
+    for hit in &resumed {
+        missing.remove(&hit.target);
+    }
+    let mut hits = resumed;
+    let end = keyspace.end();
+    let mut starts = [U256::ZERO; SLOTS];
+    let mut lens = [0u32; SLOTS];
+    let mut slot = 0usize;
+
+    // Dispatch first batch and wait for it (pipeline warm-up)
+    let (first_batch, mut next) = next_batch(keyspace.start(), end, batch);
+    starts[slot] = keyspace.start();
+    lens[slot] = first_batch;
+    backend.submit(slot, keyspace.start(), first_batch)?;
+    backend.wait(slot)?;
+
+    loop {
+        // Dispatch next batch, if any, into the other slot
+        let next_slot = 1 - slot;
+        let stopping = stop.load(Ordering::SeqCst);
+        let pending = match next {
+            Some(cur) if !stopping => {
+                let (n, after) = next_batch(cur, end, batch);
+                starts[next_slot] = cur;
+                lens[next_slot] = n;
+                backend.submit(next_slot, cur, n)?;
+                next = after;
+                true
+            }
+            _ => false,
+        };
+
+        // Verify current batch while the backend works on the next
+        let mut found = match format {
+            BatchFormat::Keys => verify_batch(backend.results(slot), secp, targets, pubkeys),
+            BatchFormat::CompressedPubKeys => {
+                verify_pubkeys(backend.results(slot), starts[slot], secp, targets, pubkeys)
+            }
+            BatchFormat::Hash160 => {
+                verify_hash160s(backend.results(slot), starts[slot], secp, targets)
+            }
+            BatchFormat::HitIndices => {
+                verify_hits(backend.results(slot), starts[slot], secp, targets, pubkeys)
+            }
+        };
+        found.sort_by_key(|hit| hit.secret_key.secret_bytes());
+        for hit in &found {
+            missing.remove(&hit.target);
+        }
+        let last_verified = starts[slot] + (lens[slot] - 1) as u64;
+        on_verified(last_verified, &found)?;
+        hits.append(&mut found);
+        if missing.is_empty() || hits.len() >= stop_after {
+            if pending {
+                backend.wait(next_slot)?;
+            }
+            return Ok(SearchOutcome::Found(hits));
+        }
+        if !pending {
+            if stopping && next.is_some() {
+                return Ok(SearchOutcome::Interrupted {
+                    last_verified,
+                    hits,
+                });
+            }
+            break;
+        }
+
+        // Wait for the next batch and make it current
+        backend.wait(next_slot)?;
+        slot = next_slot;
+    }
+
+    Ok(SearchOutcome::Exhausted(hits))
+}
+
+/// Size of the batch starting at `cur`, capped at `batch` keys, and the start
+/// of the batch after it (`None` once this batch reaches `end`).
+///
+/// The remaining width is compared as a full 256-bit value so keyspaces of
+/// any size up to the curve order are walked exactly to `end`.
+fn next_batch(cur: U256, end: U256, batch: u32) -> (u32, Option<U256>) {
+    debug_assert!(cur <= end);
+    let rem = end - cur;
+    match rem.to_u64() {
+        Some(r) if r < batch as u64 => (r as u32 + 1, None),
+        _ => (batch, Some(cur + batch as u64)),
+    }
+}
+
+/// Check a batch of 32-byte little-endian keys against `targets`, hashing
+/// each public key in the forms `pubkeys` selects.
+///
+/// Zero and out-of-range scalars are skipped. Returns every match in the
+/// batch.
+pub fn verify_batch(
+    bytes: &[u8],
+    secp: &Secp256k1<secp256k1::All>,
+    targets: &TargetSet,
+    pubkeys: PubkeyFormat,
+) -> Vec<Hit> {
+    bytes
+        .par_chunks_exact(32)
+        .flat_map_iter(|le32| match SecretKey::from_slice(&le_chunk_to_be(le32)) {
+            Ok(sk) => Hit::find(secp, sk, pubkeys, targets),
+            Err(_) => Vec::new(),
+        })
+        .collect()
+}
+
+/// Check a batch of 33-byte compressed public keys, where record `i` belongs
+/// to key `start + i`, against `targets` in the forms `pubkeys` selects.
+///
+/// All-zero records (keys outside `1..n`) are skipped. Matches are re-derived
+/// from their private keys on the CPU before being reported.
+pub fn verify_pubkeys(
+    pubkeys: &[u8],
+    start: U256,
+    secp: &Secp256k1<secp256k1::All>,
+    targets: &TargetSet,
+    format: PubkeyFormat,
+) -> Vec<Hit> {
+    let taproot = targets.has_taproot();
+    let ethereum = targets.has_ethereum();
+    let nostr = targets.has_nostr();
+    let pubkey = targets.has_pubkey();
+    let matches: Vec<usize> = pubkeys
+        .par_chunks_exact(33)
+        .enumerate()
+        .filter(|(_, pk)| {
+            if pk[0] == 0 {
+                return false;
+            }
+            if format.compressed() && targets.contains_key_hash(&hash160(pk), true) {
+                return true;
+            }
+            if format.compressed()
+                && taproot
+                && XOnlyPublicKey::from_slice(&pk[1..])
+                    .is_ok_and(|x| !targets.taproot(&taproot_output_key(secp, &x)).is_empty())
+            {
+                return true;
+            }
+            if nostr
+                && targets
+                    .nostr(pk[1..].try_into().expect("32 bytes"))
+                    .is_some()
+            {
+                return true;
+            }
+            if pubkey
+                && !targets
+                    .pubkey((*pk).try_into().expect("33 bytes"))
+                    .is_empty()
+            {
+                return true;
+            }
+            // Both remaining paths need the uncompressed key.
+            (format.uncompressed() || ethereum)
+                && PublicKey::from_slice(pk).is_ok_and(|pk| {
+                    let full = pk.serialize_uncompressed();
+                    (format.uncompressed() && targets.contains_key_hash(&hash160(&full), false))
+                        || (ethereum && targets.ethereum(&eth_address_from_pubkey(&full)).is_some())
+                })
+        })
+        .map(|(i, _)| i)
+        .collect();
+    matches
+        .into_iter()
+        .flat_map(|i| hits_at(secp, start, i as u64, targets, format))
+        .collect()
+}
+
+/// Check a batch of 20-byte HASH160s of compressed public keys, where record
+/// `i` belongs to key `start + i`, against `targets`. Only
+/// [`Target::Hash160`] targets can be matched from a HASH160; the others
+/// are skipped.
+///
+/// All-zero records (keys outside `1..n`) are skipped. Matches are re-derived
+/// from their private keys on the CPU before being reported.
+pub fn verify_hash160s(
+    hashes: &[u8],
+    start: U256,
+    secp: &Secp256k1<secp256k1::All>,
+    targets: &TargetSet,
+) -> Vec<Hit> {
+    let matches: Vec<usize> = hashes
+        .par_chunks_exact(20)
+        .enumerate()
+        .filter(|(_, h)| {
+            let h: &[u8; 20] = (*h).try_into().expect("20 bytes");
+            *h != [0; 20] && targets.contains_key_hash(h, true)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    matches
+        .into_iter()
+        .flat_map(|i| hits_at(secp, start, i as u64, targets, PubkeyFormat::Compressed))
+        .collect()
+}
+
+/// Re-verify the in-batch indices a backend reported as matching, where index
+/// `i` stands for key `start + i`. Returns the hits of those that really
+/// hash to one of `targets` in a form `pubkeys` selects.
+pub fn verify_hits(
+    indices: &[u8],
+    start: U256,
+    secp: &Secp256k1<secp256k1::All>,
+    targets: &TargetSet,
+    pubkeys: PubkeyFormat,
+) -> Vec<Hit> {
+    indices
+        .par_chunks_exact(4)
+        .flat_map_iter(|b| {
+            let idx = u32::from_le_bytes(b.try_into().expect("4 bytes"));
+            hits_at(secp, start, idx as u64, targets, pubkeys)
+        })
+        .collect()
+}
+
+/// The hits for key `start + offset`, if that key is valid.
+fn hits_at(
+    secp: &Secp256k1<secp256k1::All>,
+    start: U256,
+    offset: u64,
+    targets: &TargetSet,
+    pubkeys: PubkeyFormat,
+) -> Vec<Hit> {
+    start
+        .checked_add_u64(offset)
+        .and_then(|key| SecretKey::from_slice(&key.to_be_bytes()).ok())
+        .map_or_else(Vec::new, |sk| Hit::find(secp, sk, pubkeys, targets))
+}
+
+fn le_chunk_to_be(le32: &[u8]) -> [u8; 32] {
+    let mut be = [0u8; 32];
+    for i in 0..32 {
+        be[i] = le32[31 - i];
+    }
+    be
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SLOTS;
+    use crate::network::Network;
+
+    /// Generates like [`CpuSeq`] while recording every call and asserting the
+    /// slot protocol documented on [`CandidateBackend`].
+    #[derive(Default)]
+    struct RecordingBackend {
+        cpu: CpuSeq,
+        submitted: Vec<(usize, U256, u32)>,
+        in_flight: [bool; SLOTS],
+        /// Set this flag once this many batches have been submitted.
+        stop_after: Option<(usize, Arc<AtomicBool>)>,
+        /// Report these in-batch indices, as [`BatchFormat::HitIndices`], for
+        /// every batch instead of generating keys.
+        hit_indices: Option<Vec<u8>>,
+    }
+
+    impl CandidateBackend for RecordingBackend {
+        fn format(&self) -> BatchFormat {
+            match self.hit_indices {
+                Some(_) => BatchFormat::HitIndices,
+                None => BatchFormat::Keys,
+            }
+        }
+
+        fn submit(&mut self, slot: usize, start: U256, n: u32) -> Result<()> {
+            assert!(!self.in_flight[slot], "slot {slot} resubmitted before wait");
+            self.in_flight[slot] = true;
+            self.submitted.push((slot, start, n));
+            if let Some((after, stop)) = &self.stop_after
+                && self.submitted.len() == *after
+            {
+                stop.store(true, Ordering::SeqCst);
+            }
+            self.cpu.submit(slot, start, n)
+        }
+
+        fn wait(&mut self, slot: usize) -> Result<()> {
+            assert!(self.in_flight[slot], "wait on idle slot {slot}");
+            self.in_flight[slot] = false;
+            self.cpu.wait(slot)
+        }
+
+        fn results(&self, slot: usize) -> &[u8] {
+            assert!(
+                !self.in_flight[slot],
+                "results read before wait on slot {slot}"
+            );
+            match &self.hit_indices {
+                Some(indices) => indices,
+                None => self.cpu.results(slot),
+            }
+        }
+    }
+
+    /// Run the pipeline over `range` looking for the space-separated
+    /// addresses in `target`.
+    fn pipeline(range: &str, target: &str, batch: u32) -> (SearchOutcome, RecordingBackend) {
+        let (outcome, backend, _) = pipeline_verified(range, target, batch);
+        (outcome, backend)
+    }
+
+    /// Like [`pipeline`], also returning every `on_verified` argument.
+    fn pipeline_verified(
+        range: &str,
+        target: &str,
+        batch: u32,
+    ) -> (SearchOutcome, RecordingBackend, Vec<U256>) {
+        pipeline_with(RecordingBackend::default(), range, target, batch, None)
+    }
+
+    fn pipeline_with(
+        mut backend: RecordingBackend,
+        range: &str,
+        target: &str,
+        batch: u32,
+        stop_after: Option<usize>,
+    ) -> (SearchOutcome, RecordingBackend, Vec<U256>) {
+        let stop = backend
+            .stop_after
+            .as_ref()
+            .map_or_else(Arc::default, |(_, stop)| stop.clone());
+        let keyspace: Keyspace = range.parse().unwrap();
+        let targets: TargetSet = target.replace(' ', "\n").parse().unwrap();
+        let config = SearchConfig {
+            batch,
+            stop_after: stop_after.and_then(NonZeroUsize::new),
+            ..SearchConfig::new(keyspace, targets)
+        };
+        let mut verified = Vec::new();
+        let outcome = run_pipeline(
+            &mut backend,
+            &Secp256k1::new(),
+            &config,
+            &keyspace,
+            Vec::new(),
+            &stop,
+            &mut |k, _| {
+                verified.push(k);
+                Ok(())
+            },
+        )
+        .expect("pipeline");
+        (outcome, backend, verified)
+    }
+
+    #[test]
+    fn pipeline_reports_each_verified_prefix() {
+        let u = U256::from_u64;
+        let (_, _, verified) = pipeline_verified("1:7", "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF", 3);
+        assert_eq!(verified, vec![u(3), u(6), u(7)]);
+
+        // The batch holding the last target ends the search.
+        let (_, _, verified) = pipeline_verified("1:7", "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA", 2);
+        assert_eq!(verified, vec![u(2), u(4), u(6), u(7)]);
+        let (_, _, verified) = pipeline_verified("1:f", "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", 2);
+        assert_eq!(verified, vec![u(2)]);
+    }
+
+    #[test]
+    fn pipeline_continues_until_every_target_is_found() {
+        // Keys 1, 3 and 7; the first two share a batch.
+        let (outcome, backend) = pipeline(
+            "1:ff",
+            "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH \
+             1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb",
+            4,
+        );
+        let SearchOutcome::Found(hits) = outcome else {
+            panic!("expected every target, got {outcome:?}");
+        };
+        assert_eq!(keys(&hits), [1, 3, 7]);
+        assert!(!backend.in_flight.contains(&true));
+        // Nothing past the batch holding key 7, plus the prefetched one.
+        assert_eq!(backend.submitted.len(), 3);
+    }
+
+    #[test]
+    fn pipeline_reports_hits_in_key_order() {
+        // Indices arrive unordered, as from the GPU's atomic counter; 4 is a
+        // false positive.
+        let backend = RecordingBackend {
+            hit_indices: Some(
+                [6u32, 4, 0, 2]
+                    .iter()
+                    .flat_map(|i| i.to_le_bytes())
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let (outcome, _, _) = pipeline_with(
+            backend,
+            "1:10",
+            "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH \
+             1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb",
+            16,
+            None,
+        );
+        assert_eq!(keys(outcome.hits()), [1, 3, 7]);
+    }
+
+    #[test]
+    fn pipeline_stops_after_n_hits_but_reports_whole_batch() {
+        let targets = "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH \
+                       1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb";
+        // Keys 1 and 3 share the first batch: both are reported.
+        let (outcome, backend, verified) =
+            pipeline_with(RecordingBackend::default(), "1:ff", targets, 4, Some(1));
+        let SearchOutcome::Found(hits) = outcome else {
+            panic!("expected to stop on a hit, got {outcome:?}");
+        };
+        assert_eq!(hits.len(), 2);
+        assert_eq!(verified, vec![U256::from_u64(4)]);
+        assert_eq!(backend.submitted.len(), 2);
+
+        let (outcome, _, _) =
+            pipeline_with(RecordingBackend::default(), "1:ff", targets, 4, Some(3));
+        assert_eq!(outcome.hits().len(), 3);
+    }
+
+    #[test]
+    fn pipeline_reports_targets_found_when_others_are_missing() {
+        let (outcome, backend) = pipeline(
+            "1:40",
+            "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF 19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA",
+            16,
+        );
+        let SearchOutcome::Exhausted(hits) = outcome else {
+            panic!("expected the keyspace to run out, got {outcome:?}");
+        };
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].address, "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA");
+        assert_eq!(hits[0].target.to_string(), hits[0].address);
+        assert_eq!(backend.submitted.last().unwrap().1, U256::from_u64(0x31));
+    }
+
+    #[test]
+    fn pipeline_finishes_in_flight_batch_when_stopped() {
+        let u = U256::from_u64;
+        // Stop is requested while batch 2 (keys 4..=6) is in flight.
+        let backend = RecordingBackend {
+            stop_after: Some((2, Arc::default())),
+            ..Default::default()
+        };
+        let (outcome, backend, verified) = pipeline_with(
+            backend,
+            "1:f",
+            "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF",
+            3,
+            None,
+        );
+        let SearchOutcome::Interrupted { last_verified, .. } = outcome else {
+            panic!("expected an interruption, got {outcome:?}");
+        };
+        assert_eq!(last_verified, u(6));
+        assert_eq!(verified, vec![u(3), u(6)]);
+        assert_eq!(backend.submitted, vec![(0, u(1), 3), (1, u(4), 3)]);
+        assert!(!backend.in_flight.contains(&true));
+    }
+
+    #[test]
+    fn pipeline_reports_hit_in_batch_finished_after_stop() {
+        let backend = RecordingBackend {
+            stop_after: Some((2, Arc::default())),
+            ..Default::default()
+        };
+        // Key 7 is in the second batch, which is in flight when stop is set.
+        let (outcome, _, _) = pipeline_with(
+            backend,
+            "1:f",
+            "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA",
+            4,
+            None,
+        );
+        assert!(matches!(outcome, SearchOutcome::Found(_)));
+    }
+
+    #[test]
+    fn pipeline_alternates_slots_and_finds_hit_in_last_batch() {
+        let (outcome, backend) = pipeline("1:7", "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA", 2);
+        let SearchOutcome::Found(hits) = outcome else {
+            panic!("expected a hit");
+        };
+        assert_eq!(hits[0].secret_key.secret_bytes()[31], 7);
+        let u = U256::from_u64;
+        assert_eq!(
+            backend.submitted,
+            vec![(0, u(1), 2), (1, u(3), 2), (0, u(5), 2), (1, u(7), 1)]
+        );
+    }
+
+    #[test]
+    fn pipeline_stops_at_first_hit() {
+        // Key 1 is in the first batch; only the prefetched second batch is submitted.
+        let (outcome, backend) = pipeline("1:ff", "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", 16);
+        assert!(matches!(outcome, SearchOutcome::Found(_)));
+        assert_eq!(backend.submitted.len(), 2);
+    }
+
+    #[test]
+    fn pipeline_covers_range_on_miss() {
+        let (outcome, backend) = pipeline("10:64", "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF", 7);
+        assert!(matches!(&outcome, SearchOutcome::Exhausted(hits) if hits.is_empty()));
+        let mut expect = U256::from_u64(0x10);
+        for &(_, start, n) in &backend.submitted {
+            assert_eq!(start, expect);
+            expect = start + n as u64;
+        }
+        assert_eq!(expect, U256::from_u64(0x65));
+        assert_eq!(backend.in_flight, [false; SLOTS]);
+    }
+
+    #[test]
+    fn keyspace_parses_and_rejects_reversed_range() {
+        let ks: Keyspace = "8:f".parse().expect("keyspace");
+        assert_eq!(ks.start(), U256::from_u64(8));
+        assert_eq!(ks.end(), U256::from_u64(0xf));
+        assert!("f:8".parse::<Keyspace>().is_err());
+        assert!("f".parse::<Keyspace>().is_err());
+    }
+
+    /// Walk `[start, end]` the way `SearchEngine::run` does, returning every
+    /// batch as `(start, len)`.
+    fn batches(start: U256, end: U256, batch: u32) -> Vec<(U256, u32)> {
+        let mut out = Vec::new();
+        let mut next = Some(start);
+        while let Some(cur) = next {
+            let (n, after) = next_batch(cur, end, batch);
+            out.push((cur, n));
+            next = after;
+        }
+        out
+    }
+
+    fn assert_covers(start: U256, end: U256, batch: u32) -> Vec<(U256, u32)> {
+        let b = batches(start, end, batch);
+        let mut expect = start;
+        for &(s, n) in &b {
+            assert_eq!(s, expect);
+            assert!(n >= 1 && n <= batch);
+            expect = s + n as u64;
+        }
+        assert_eq!(expect, end + 1);
+        b
+    }
+
+    #[test]
+    fn next_batch_single_key_range() {
+        assert_eq!(
+            batches(U256::ONE, U256::ONE, 1_000_000),
+            vec![(U256::ONE, 1)]
+        );
+    }
+
+    #[test]
+    fn next_batch_straddles_64_bit_limb() {
+        let two64 = U256::ONE << 64;
+        let b = assert_covers(two64 - 3, two64 + 4, 3);
+        assert_eq!(b.iter().map(|&(_, n)| n).collect::<Vec<_>>(), vec![3, 3, 2]);
+    }
+
+    #[test]
+    fn next_batch_straddles_128_bit_limb() {
+        let two128 = U256::ONE << 128;
+        let b = assert_covers(two128 - 5, two128 + 5, 4);
+        assert_eq!(b.iter().map(|&(_, n)| n).collect::<Vec<_>>(), vec![4, 4, 3]);
+    }
+
+    #[test]
+    fn next_batch_does_not_truncate_wide_ranges() {
+        // Width 2^64 + 1: the old low-64-bit arithmetic saw this as one key.
+        let end = U256::ONE << 64;
+        assert_eq!(
+            next_batch(U256::ZERO, end, 1000),
+            (1000, Some(U256::from_u64(1000)))
+        );
+
+        let ks: Keyspace = "1:ffffffffffffffffffff".parse().unwrap();
+        let (n, after) = next_batch(ks.start(), ks.end(), 1_000_000);
+        assert_eq!(n, 1_000_000);
+        assert_eq!(after, Some(U256::from_u64(1_000_001)));
+
+        // The final batch lands exactly on `end`.
+        let cur = ks.end() - 9;
+        assert_eq!(next_batch(cur, ks.end(), 1_000_000), (10, None));
+    }
+
+    #[test]
+    fn next_batch_reaches_top_of_u256() {
+        let b = assert_covers(U256::MAX - 6, U256::MAX - 1, 4);
+        assert_eq!(b.len(), 2);
+        assert_eq!(
+            batches(U256::MAX - 3, U256::MAX, 4),
+            vec![(U256::MAX - 3, 4)]
+        );
+    }
+
+    #[test]
+    fn verify_pubkeys_maps_record_index_to_key() {
+        let secp = Secp256k1::new();
+        let target: TargetSet = "1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k".parse().unwrap();
+        let start = U256::from_u64(0x10);
+        let bytes = small_pubkeys(&secp, 0x10..=0x1f).concat();
+        let hits = verify_pubkeys(&bytes, start, &secp, &target, PubkeyFormat::Compressed);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].address, "1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k");
+        assert_eq!(hits[0].secret_key.secret_bytes()[31], 0x15);
+
+        // Zero records are skipped rather than hashed.
+        let zeros = vec![0u8; 33 * 4];
+        assert!(verify_pubkeys(&zeros, start, &secp, &target, PubkeyFormat::Both).is_empty());
+    }
+
+    #[test]
+    fn verify_hash160s_maps_record_index_to_key() {
+        let secp = Secp256k1::new();
+        let targets: TargetSet =
+            "1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k\n1PitScNLyp2HCygzadCh7FveTnfmpPbfp8"
+                .parse()
+                .unwrap();
+        let start = U256::from_u64(0x10);
+        let bytes: Vec<u8> = small_pubkeys(&secp, 0x10..=0x1f)
+            .iter()
+            .flat_map(|pk| hash160(pk))
+            .collect();
+        let hits = verify_hash160s(&bytes, start, &secp, &targets);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].secret_key.secret_bytes()[31], 0x15);
+
+        // A record that matches but does not belong to its key is rejected.
+        assert!(verify_hash160s(&bytes, start + 1, &secp, &targets).is_empty());
+    }
+
+    #[test]
+    fn verify_hits_rejects_false_positives() {
+        let secp = Secp256k1::new();
+        let target: TargetSet = "1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k".parse().unwrap();
+        let start = U256::from_u64(0x10);
+        let indices: Vec<u8> = [3u32, 5, 9].iter().flat_map(|i| i.to_le_bytes()).collect();
+        let hits = verify_hits(&indices, start, &secp, &target, PubkeyFormat::Compressed);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].secret_key.secret_bytes()[31], 0x15);
+
+        let wrong: Vec<u8> = [3u32, 9].iter().flat_map(|i| i.to_le_bytes()).collect();
+        assert!(verify_hits(&wrong, start, &secp, &target, PubkeyFormat::Compressed).is_empty());
+        assert!(verify_hits(&[], start, &secp, &target, PubkeyFormat::Compressed).is_empty());
+    }
+
+    /// Compressed public keys of the small private keys `keys`.
+    fn small_pubkeys(
+        secp: &Secp256k1<secp256k1::All>,
+        keys: std::ops::RangeInclusive<u8>,
+    ) -> Vec<[u8; 33]> {
+        keys.map(|k| {
+            let mut be = [0u8; 32];
+            be[31] = k;
+            let sk = SecretKey::from_slice(&be).unwrap();
+            PublicKey::from_secret_key(secp, &sk).serialize()
+        })
+        .collect()
+    }
+
+    /// The private keys of `hits`, which are all below 256.
+    fn keys(hits: &[Hit]) -> Vec<u8> {
+        hits.iter()
+            .map(|h| h.secret_key.secret_bytes()[31])
+            .collect()
+    }
+
+    /// Little-endian 32-byte records for keys `1..=n`.
+    fn small_keys(n: u8) -> Vec<u8> {
+        (1..=n)
+            .flat_map(|k| {
+                let mut le = [0u8; 32];
+                le[0] = k;
+                le
+            })
+            .collect()
+    }
+
+    #[test]
+    fn verify_batch_hashes_the_selected_pubkey_forms() {
+        let secp = Secp256k1::new();
+        // Key 1 uncompressed and key 3 compressed.
+        let targets: TargetSet =
+            "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm\n1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb"
+                .parse()
+                .unwrap();
+        let bytes = small_keys(4);
+        let found = |pubkeys| {
+            verify_batch(&bytes, &secp, &targets, pubkeys)
+                .iter()
+                .map(|h| (h.secret_key.secret_bytes()[31], h.compressed))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(found(PubkeyFormat::Compressed), vec![(3, true)]);
+        assert_eq!(found(PubkeyFormat::Uncompressed), vec![(1, false)]);
+        assert_eq!(found(PubkeyFormat::Both), vec![(1, false), (3, true)]);
+
+        let hit = &verify_batch(&bytes, &secp, &targets, PubkeyFormat::Both)[0];
+        assert_eq!(hit.address, "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+        assert_eq!(hit.public_key.len(), 65);
+        assert_eq!(
+            hit.wif.as_deref(),
+            Some("5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf")
+        );
+        assert_eq!(hit.form(), PubkeyFormat::Uncompressed);
+    }
+
+    #[test]
+    fn verify_batch_reports_every_address_kind_of_a_key() {
+        let secp = Secp256k1::new();
+        // Key 1 as P2WPKH, compressed P2PKH and uncompressed P2PKH.
+        let targets: TargetSet = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n\
+             1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH\n\
+             1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm"
+            .parse()
+            .unwrap();
+        let bytes = small_keys(2);
+        let addresses: Vec<_> = verify_batch(&bytes, &secp, &targets, PubkeyFormat::Both)
+            .into_iter()
+            .map(|h| h.address)
+            .collect();
+        assert_eq!(
+            addresses,
+            [
+                "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm",
+            ]
+        );
+
+        // SegWit outputs never match the uncompressed key.
+        let segwit: TargetSet = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        assert!(verify_batch(&bytes, &secp, &segwit, PubkeyFormat::Uncompressed).is_empty());
+    }
+
+    #[test]
+    fn verify_finds_nested_segwit_through_the_redeem_script() {
+        let secp = Secp256k1::new();
+        let targets: TargetSet = "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN".parse().unwrap();
+        assert!(targets.has_nested_segwit());
+        let hits = verify_batch(&small_keys(3), &secp, &targets, PubkeyFormat::Both);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].compressed);
+        assert_eq!(hits[0].address, "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN");
+        assert_eq!(
+            hex::encode(hits[0].redeem_script.as_ref().unwrap()),
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+
+        // The GPU formats that leave hashing, or the second hash, to the CPU.
+        let key_hash = hash160(&hits[0].public_key);
+        let hashes: Vec<u8> = [[0; 20], key_hash, [7; 20]].concat();
+        assert_eq!(
+            verify_hash160s(&hashes, U256::ZERO, &secp, &targets).len(),
+            1
+        );
+        assert!(!targets.contains_key_hash(&key_hash, false));
+    }
+
+    #[test]
+    fn verify_finds_taproot_through_the_tweaked_key() {
+        let secp = Secp256k1::new();
+        // Key 1 as P2TR (BIP86, no script tree) and key 3 as P2PKH.
+        let targets: TargetSet = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9\n\
+             1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb"
+            .parse()
+            .unwrap();
+        let hits = verify_batch(&small_keys(4), &secp, &targets, PubkeyFormat::Both);
+        assert_eq!(keys(&hits), [1, 3]);
+        assert!(hits[0].compressed);
+        assert_eq!(
+            hits[0].address,
+            "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9"
+        );
+        assert!(
+            verify_batch(&small_keys(4), &secp, &targets, PubkeyFormat::Uncompressed).is_empty()
+        );
+
+        let pubkeys: Vec<u8> = hits.iter().flat_map(|h| h.public_key.clone()).collect();
+        let found = verify_pubkeys(
+            &pubkeys,
+            U256::ONE,
+            &secp,
+            &targets,
+            PubkeyFormat::Compressed,
+        );
+        // Record 1 is key 3's public key but stands for key 2.
+        assert_eq!(keys(&found), [1]);
+    }
+
+    #[test]
+    fn verify_finds_ethereum_through_the_uncompressed_key() {
+        let secp = Secp256k1::new();
+        // Key 2 on Ethereum and key 3 as compressed P2PKH.
+        let targets: TargetSet = "0x2B5AD5c4795c026514f8317c7a215E218DcCD6cF\n\
+             1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb"
+            .parse()
+            .unwrap();
+        let hits = verify_batch(&small_keys(4), &secp, &targets, PubkeyFormat::Compressed);
+        assert_eq!(keys(&hits), [2, 3]);
+        assert_eq!(
+            hits[0].address,
+            "0x2B5AD5c4795c026514f8317c7a215E218DcCD6cF"
+        );
+        assert!(!hits[0].compressed);
+        assert_eq!(hits[0].public_key.len(), 65);
+        assert_eq!(hits[0].wif, None);
+
+        let pubkeys = small_pubkeys(&secp, 1..=4).concat();
+        let found = verify_pubkeys(
+            &pubkeys,
+            U256::ONE,
+            &secp,
+            &targets,
+            PubkeyFormat::Compressed,
+        );
+        assert_eq!(keys(&found), [2, 3]);
+        assert!(verify_hash160s(&[0; 80], U256::ONE, &secp, &targets).is_empty());
+    }
+
+    #[test]
+    fn verify_finds_nostr_keys_by_x_coordinate() {
+        let secp = Secp256k1::new();
+        // Key 3, and key 6 whose public key has an odd Y.
+        let targets: TargetSet =
+            "npub1lycg5qvjtrp3qjf5f7zl382j9x6nrjz9sdhenvyxq8c3808qxmus6gq266\n\
+             fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556"
+                .parse()
+                .unwrap();
+        let hits = verify_batch(&small_keys(8), &secp, &targets, PubkeyFormat::Uncompressed);
+        assert_eq!(keys(&hits), [3, 6]);
+        assert_eq!(
+            hits[0].nsec.as_deref(),
+            Some("nsec1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqps52s3re")
+        );
+        assert_eq!(hits[0].wif, None);
+        assert_eq!(hits[0].descriptor, None);
+        assert_eq!(hits[1].public_key[0], 0x03);
+
+        let pubkeys: Vec<u8> = hits.iter().flat_map(|h| h.public_key.clone()).collect();
+        let found = verify_pubkeys(
+            &pubkeys,
+            U256::from_u64(3),
+            &secp,
+            &targets,
+            PubkeyFormat::Compressed,
+        );
+        // Record 1 is key 6's public key but stands for key 4.
+        assert_eq!(keys(&found), [3]);
+    }
+
+    #[test]
+    fn verify_matches_public_keys_as_points() {
+        let secp = Secp256k1::new();
+        // Key 1 given uncompressed and key 3 compressed.
+        let targets: TargetSet =
+            "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8\n\
+             02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+                .parse()
+                .unwrap();
+        let hits = verify_batch(&small_keys(4), &secp, &targets, PubkeyFormat::Compressed);
+        assert_eq!(keys(&hits), [1, 3]);
+        assert!(!hits[0].compressed);
+        assert_eq!(hits[0].public_key.len(), 65);
+        assert_eq!(hits[0].address, "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+        assert_eq!(
+            hits[0].wif.as_deref(),
+            Some("5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf")
+        );
+        assert_eq!(hits[1].address, "1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb");
+
+        let pubkeys = small_pubkeys(&secp, 1..=4).concat();
+        let found = verify_pubkeys(
+            &pubkeys,
+            U256::ONE,
+            &secp,
+            &targets,
+            PubkeyFormat::Uncompressed,
+        );
+        assert_eq!(keys(&found), [1, 3]);
+    }
+
+    #[test]
+    fn verify_pubkeys_decompresses_for_uncompressed_targets() {
+        let secp = Secp256k1::new();
+        let targets: TargetSet = "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm".parse().unwrap();
+        let bytes = small_pubkeys(&secp, 1..=4).concat();
+        let hits = verify_pubkeys(&bytes, U256::ONE, &secp, &targets, PubkeyFormat::Both);
+        assert_eq!(hits.len(), 1);
+        assert!(!hits[0].compressed);
+        assert!(
+            verify_pubkeys(&bytes, U256::ONE, &secp, &targets, PubkeyFormat::Compressed).is_empty()
+        );
+    }
+
+    #[test]
+    fn verify_batch_finds_every_target_in_batch() {
+        let secp = Secp256k1::new();
+        let targets: TargetSet =
+            "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA\n1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+                .parse()
+                .unwrap();
+        let mut bytes = Vec::new();
+        for k in 0u8..=7 {
+            let mut le = [0u8; 32];
+            le[0] = k;
+            bytes.extend_from_slice(&le);
+        }
+        let hits = verify_batch(&bytes, &secp, &targets, PubkeyFormat::Compressed);
+        assert_eq!(keys(&hits), [1, 7]);
+        assert_eq!(hits[1].address, "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA");
+    }
+
+    #[test]
+    fn verify_writes_wif_for_the_target_chain() {
+        // Regtest shares testnet's Base58 prefixes, but not its HRP.
+        let regtest = Network::Regtest.chain();
+        let target = Target::parse("mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r", &regtest).unwrap();
+        let hits = verify_batch(
+            &small_keys(1),
+            &Secp256k1::new(),
+            &target.into(),
+            PubkeyFormat::Compressed,
+        );
+        assert_eq!(
+            hits[0].wif.as_deref(),
+            Some("cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN87JcbXMTcA")
+        );
+    }
+}
@@ -0,0 +1,1096 @@
+//! Search targets: the addresses, public keys, scripts and descriptors a
+//! candidate key is matched against, and how they are parsed.
+
+use crate::address::{
+    decode_eth_address, decode_npub, decode_p2pkh_to_hash160, decode_p2sh_to_hash160,
+    decode_p2tr_to_output_key, decode_p2wpkh_to_hash160, eth_checksum_encode, hash160,
+    npub_from_xonly, p2pkh_from_hash160, p2sh_from_hash160, p2tr_from_output_key,
+    p2wpkh_from_hash160, p2wpkh_script, taproot_output_key,
+};
+use crate::chain::Chain;
+use crate::descriptor;
+use crate::network::Network;
+use crate::search::PubkeyFormat;
+use anyhow::{Context, Result, anyhow};
+use secp256k1::{PublicKey, Secp256k1, XOnlyPublicKey};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How a target address encodes its HASH160.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AddressKind {
+    /// Base58Check, version `0x00` (`1…`).
+    P2pkh,
+    /// Bech32 SegWit v0 key hash (`bc1q…`); commits to a compressed key.
+    P2wpkh,
+    /// Base58Check, version `0x05` (`3…`), wrapping a P2WPKH redeem script;
+    /// commits to the HASH160 of that script.
+    P2shP2wpkh,
+}
+
+/// What a candidate key must produce to count as a match.
+///
+/// Targets order by variant first, so a [`TargetSet`] keeps the targets of
+/// each match path together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Target {
+    /// An address committing to a HASH160: of the public key, or of the
+    /// redeem script for [`AddressKind::P2shP2wpkh`].
+    Hash160 {
+        hash160: [u8; 20],
+        kind: AddressKind,
+        chain: Chain,
+    },
+    /// An Ethereum (`0x…`) address: the last 20 bytes of the Keccak-256 of
+    /// the uncompressed public key.
+    Ethereum { address: [u8; 20] },
+    /// A Nostr public key (`npub…`): the x coordinate of the public key,
+    /// compared directly.
+    Nostr { pubkey: [u8; 32] },
+    /// An exposed public key, compared as a point with no hashing. `key` is
+    /// the compressed serialization; `compressed` records the form it was
+    /// given in, which sets the address and WIF reported for it.
+    PubKey {
+        key: [u8; 33],
+        compressed: bool,
+        chain: Chain,
+    },
+    /// A Taproot (`bc1p…`) key-path address: the BIP341 output key of an
+    /// internal key with no script tree.
+    Taproot { output_key: [u8; 32], chain: Chain },
+}
+
+impl Target {
+    /// Match the public key whose HASH160 equals `hash160`, reported as a
+    /// Bitcoin P2PKH address.
+    pub fn from_hash160(hash160: [u8; 20]) -> Self {
+        Self::new(AddressKind::P2pkh, hash160)
+    }
+
+    /// Match the public key whose HASH160 equals `hash160`, reported as a
+    /// Bitcoin `kind` address.
+    pub fn new(kind: AddressKind, hash160: [u8; 20]) -> Self {
+        Self::Hash160 {
+            hash160,
+            kind,
+            chain: Chain::BITCOIN,
+        }
+    }
+
+    /// Parse a P2PKH, P2WPKH, P2SH-P2WPKH or P2TR address of `chain`, an
+    /// Ethereum address, a Nostr public key as an `npub` or 64 hex digits,
+    /// a hex SEC1 public key reported as a P2PKH address of `chain`, or an
+    /// output descriptor (see [`Target::from_descriptor`]).
+    pub fn parse(s: &str, chain: &Chain) -> Result<Self> {
+        if s.contains('(') {
+            return Self::from_descriptor(s, chain);
+        }
+        if matches!(s.len(), 66 | 130) && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            let pk = PublicKey::from_slice(&hex::decode(s)?)
+                .map_err(|_| anyhow!("{s} is not a valid public key"))?;
+            return Ok(Self::PubKey {
+                key: pk.serialize(),
+                compressed: s.len() == 66,
+                chain: *chain,
+            });
+        }
+        if s.starts_with("0x") {
+            return decode_eth_address(s).map(|address| Self::Ethereum { address });
+        }
+        if s.to_ascii_lowercase().starts_with("npub1") {
+            return decode_npub(s).and_then(Self::nostr);
+        }
+        if s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            let mut pubkey = [0u8; 32];
+            hex::decode_to_slice(s, &mut pubkey)?;
+            return Self::nostr(pubkey);
+        }
+        let chain = *chain;
+        let segwit = chain.hrp.map(|hrp| format!("{hrp}1"));
+        let hash160 = |kind| {
+            move |hash160| Self::Hash160 {
+                hash160,
+                kind,
+                chain,
+            }
+        };
+        let base58 = || match decode_p2sh_to_hash160(s, &chain) {
+            Ok(h) => Ok(hash160(AddressKind::P2shP2wpkh)(h)),
+            Err(_) => decode_p2pkh_to_hash160(s, &chain).map(hash160(AddressKind::P2pkh)),
+        };
+        // Bech32 strings are all one case, so a mixed-case string is Base58
+        // even when its prefix spells the HRP.
+        let lower = s.to_ascii_lowercase();
+        if s != lower && s != s.to_ascii_uppercase() {
+            return base58();
+        }
+        let segwit = match segwit
+            .as_deref()
+            .and_then(|prefix| lower.strip_prefix(prefix))
+        {
+            Some(program) if program.starts_with('p') => decode_p2tr_to_output_key(s, &chain)
+                .map(|output_key| Self::Taproot { output_key, chain }),
+            Some(_) => decode_p2wpkh_to_hash160(s, &chain).map(hash160(AddressKind::P2wpkh)),
+            None => return base58(),
+        };
+        segwit.or_else(|err| base58().map_err(|_| err))
+    }
+
+    /// The target paying to the output script `script`: P2PKH, P2WPKH,
+    /// P2SH or P2TR, with addresses reported on `chain`. P2SH scripts are
+    /// searched as P2SH-P2WPKH, the only P2SH form a lone key spends.
+    pub fn from_script(script: &[u8], chain: &Chain) -> Result<Self> {
+        let chain = *chain;
+        let hash160 = |kind, h: &[u8]| Self::Hash160 {
+            hash160: h.try_into().expect("20 bytes"),
+            kind,
+            chain,
+        };
+        let segwit = |target| match chain.hrp {
+            Some(_) => Ok(target),
+            None => Err(anyhow!("chain has no SegWit addresses")),
+        };
+        match script {
+            [0x76, 0xa9, 0x14, h @ .., 0x88, 0xac] if h.len() == 20 => {
+                Ok(hash160(AddressKind::P2pkh, h))
+            }
+            [0xa9, 0x14, h @ .., 0x87] if h.len() == 20 => Ok(hash160(AddressKind::P2shP2wpkh, h)),
+            [0x00, 0x14, h @ ..] if h.len() == 20 => segwit(hash160(AddressKind::P2wpkh, h)),
+            [0x51, 0x20, k @ ..] if k.len() == 32 => segwit(Self::Taproot {
+                output_key: k.try_into().expect("32 bytes"),
+                chain,
+            }),
+            _ => Err(anyhow!(
+                "{} is not a P2PKH, P2WPKH, P2SH or P2TR script",
+                hex::encode(script)
+            )),
+        }
+    }
+
+    /// The target of a BIP380 output descriptor, whose checksum is checked
+    /// if it has one: `pkh`, `wpkh`, `sh(wpkh)` or `tr` of a hex public key,
+    /// or `addr` of an address of `chain`. A `pkh` key is a
+    /// [`Target::PubKey`], compared as a point so that it is found in either
+    /// form.
+    pub fn from_descriptor(s: &str, chain: &Chain) -> Result<Self> {
+        let desc = descriptor::strip_checksum(s)?;
+        let chain = *chain;
+        let (func, arg) = desc
+            .strip_suffix(')')
+            .and_then(|desc| desc.split_once('('))
+            .ok_or_else(|| anyhow!("{desc} is not a descriptor"))?;
+        match func {
+            "addr" => {
+                return match Self::parse(arg, &chain)? {
+                    target @ (Self::Hash160 { .. } | Self::Taproot { .. })
+                        if !arg.contains('(') =>
+                    {
+                        Ok(target)
+                    }
+                    _ => Err(anyhow!(
+                        "addr() takes a P2PKH, P2WPKH, P2SH or P2TR address"
+                    )),
+                };
+            }
+            "pkh" | "wpkh" | "sh" | "tr" => {}
+            _ => {
+                return Err(anyhow!(
+                    "unsupported descriptor {func}(); expected pkh, wpkh, sh(wpkh), tr or addr"
+                ));
+            }
+        }
+        let key = match func {
+            "sh" => arg
+                .strip_prefix("wpkh(")
+                .and_then(|key| key.strip_suffix(')'))
+                .ok_or_else(|| anyhow!("sh() descriptors must wrap wpkh()"))?,
+            _ => arg,
+        };
+        // A key origin `[fingerprint/path]` only records where the key came
+        // from.
+        let key = match key.strip_prefix('[') {
+            Some(origin) => origin.split_once(']').map_or(key, |(_, key)| key),
+            None => key,
+        };
+        let bytes = hex::decode(key).map_err(|_| anyhow!("{key} is not a hex public key"))?;
+        let invalid = || anyhow!("{key} is not a valid public key");
+        let compressed = |bytes: &[u8]| match PublicKey::from_slice(bytes) {
+            Ok(pk) if bytes.len() == 33 => Ok(pk),
+            Ok(_) => Err(anyhow!("{func}() takes a compressed public key")),
+            Err(_) => Err(invalid()),
+        };
+        let key_hash = |kind, hash160| Self::Hash160 {
+            hash160,
+            kind,
+            chain,
+        };
+        let segwit = |target| match chain.hrp {
+            Some(_) => Ok(target),
+            None => Err(anyhow!("chain has no SegWit addresses")),
+        };
+        match func {
+            "pkh" => {
+                let pk = PublicKey::from_slice(&bytes).map_err(|_| invalid())?;
+                Ok(Self::PubKey {
+                    key: pk.serialize(),
+                    compressed: bytes.len() == 33,
+                    chain,
+                })
+            }
+            "wpkh" => {
+                compressed(&bytes)?;
+                segwit(key_hash(AddressKind::P2wpkh, hash160(&bytes)))
+            }
+            "sh" => {
+                compressed(&bytes)?;
+                let script = p2wpkh_script(&hash160(&bytes));
+                segwit(key_hash(AddressKind::P2shP2wpkh, hash160(&script)))
+            }
+            _ => {
+                let internal_key = match bytes.len() {
+                    32 => XOnlyPublicKey::from_slice(&bytes).map_err(|_| invalid())?,
+                    _ => compressed(&bytes)?.x_only_public_key().0,
+                };
+                let secp = Secp256k1::verification_only();
+                segwit(Self::Taproot {
+                    output_key: taproot_output_key(&secp, &internal_key),
+                    chain,
+                })
+            }
+        }
+    }
+
+    /// A Nostr target, if `pubkey` is the x coordinate of a curve point.
+    fn nostr(pubkey: [u8; 32]) -> Result<Self> {
+        XOnlyPublicKey::from_slice(&pubkey)
+            .map_err(|_| anyhow!("{} is not an x-only public key", hex::encode(pubkey)))?;
+        Ok(Self::Nostr { pubkey })
+    }
+
+    /// The chain whose prefixes this target's address uses; `None` for
+    /// Ethereum and Nostr.
+    pub fn chain(&self) -> Option<&Chain> {
+        match self {
+            Self::Hash160 { chain, .. }
+            | Self::PubKey { chain, .. }
+            | Self::Taproot { chain, .. } => Some(chain),
+            Self::Ethereum { .. } | Self::Nostr { .. } => None,
+        }
+    }
+
+    /// The address a hit on this target is reported as: the P2PKH address
+    /// of a [`Target::PubKey`], and the target itself otherwise.
+    pub fn address(&self) -> String {
+        match self {
+            Self::PubKey {
+                key,
+                compressed,
+                chain,
+            } => p2pkh_from_hash160(&hash160(&serialize_point(key, *compressed)), chain),
+            _ => self.to_string(),
+        }
+    }
+
+    /// The HASH160 this target commits to, if it is a [`Target::Hash160`].
+    pub fn hash160(&self) -> Option<&[u8; 20]> {
+        match self {
+            Self::Hash160 { hash160, .. } => Some(hash160),
+            _ => None,
+        }
+    }
+
+    /// The output key this target pays to, if it is a [`Target::Taproot`].
+    pub fn output_key(&self) -> Option<&[u8; 32]> {
+        match self {
+            Self::Taproot { output_key, .. } => Some(output_key),
+            _ => None,
+        }
+    }
+
+    /// Whether a search of the `pubkeys` forms can match this target. SegWit
+    /// outputs pay to a compressed key; every other target matches either
+    /// form.
+    pub fn reachable(&self, pubkeys: PubkeyFormat) -> bool {
+        pubkeys.compressed()
+            || !matches!(
+                self,
+                Self::Hash160 {
+                    kind: AddressKind::P2wpkh | AddressKind::P2shP2wpkh,
+                    ..
+                } | Self::Taproot { .. }
+            )
+    }
+
+    /// Whether a key matching in `compressed` form spends this address. Only
+    /// P2PKH accepts uncompressed keys.
+    pub fn accepts(&self, compressed: bool) -> bool {
+        compressed
+            || matches!(
+                self,
+                Self::Hash160 {
+                    kind: AddressKind::P2pkh,
+                    ..
+                }
+            )
+    }
+
+    /// The descriptor, with its checksum, of the script this target pays
+    /// to with `key` as its key expression; `None` for Ethereum and Nostr.
+    pub(crate) fn descriptor(&self, key: &str) -> Option<String> {
+        let desc = match self {
+            Self::Hash160 { kind, .. } => match kind {
+                AddressKind::P2pkh => format!("pkh({key})"),
+                AddressKind::P2wpkh => format!("wpkh({key})"),
+                AddressKind::P2shP2wpkh => format!("sh(wpkh({key}))"),
+            },
+            Self::PubKey { .. } => format!("pkh({key})"),
+            Self::Taproot { .. } => format!("tr({key})"),
+            Self::Ethereum { .. } | Self::Nostr { .. } => return None,
+        };
+        Some(descriptor::with_checksum(&desc).expect("descriptor characters"))
+    }
+
+    pub(crate) fn is_nested_segwit(&self) -> bool {
+        matches!(
+            self,
+            Self::Hash160 {
+                kind: AddressKind::P2shP2wpkh,
+                ..
+            }
+        )
+    }
+}
+
+impl FromStr for Target {
+    type Err = anyhow::Error;
+
+    /// Parse an address, or an `addr()` descriptor, of the Bitcoin network
+    /// its prefix names; see [`Network::of_address`].
+    fn from_str(s: &str) -> Result<Self> {
+        let addr = s
+            .strip_prefix("addr(")
+            .and_then(|addr| addr.split(')').next())
+            .unwrap_or(s);
+        Self::parse(s, &Network::of_address(addr).unwrap_or_default().chain())
+    }
+}
+
+impl fmt::Display for Target {
+    /// The address of this target.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&match self {
+            Self::Hash160 {
+                hash160,
+                kind,
+                chain,
+            } => match kind {
+                AddressKind::P2pkh => p2pkh_from_hash160(hash160, chain),
+                AddressKind::P2wpkh => p2wpkh_from_hash160(hash160, chain),
+                AddressKind::P2shP2wpkh => p2sh_from_hash160(hash160, chain),
+            },
+            Self::Ethereum { address } => eth_checksum_encode(address),
+            Self::Nostr { pubkey } => npub_from_xonly(pubkey),
+            Self::PubKey {
+                key, compressed, ..
+            } => hex::encode(serialize_point(key, *compressed)),
+            Self::Taproot { output_key, chain } => p2tr_from_output_key(output_key, chain),
+        })
+    }
+}
+
+/// SEC1 serialization of the point whose compressed form is `key`.
+pub(crate) fn serialize_point(key: &[u8; 33], compressed: bool) -> Vec<u8> {
+    if compressed {
+        return key.to_vec();
+    }
+    PublicKey::from_slice(key)
+        .expect("public key targets hold valid points")
+        .serialize_uncompressed()
+        .to_vec()
+}
+
+/// Targets searched for in one pass, kept sorted without duplicates so
+/// membership is a binary search. Addresses of different kinds may share a
+/// HASH160.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TargetSet {
+    targets: Vec<Target>,
+    /// Number of [`AddressKind::P2shP2wpkh`] targets; key hashes only take
+    /// the second hash step through the redeem script when this is nonzero.
+    nested: usize,
+}
+
+impl TargetSet {
+    /// Read a targets file: one address or descriptor per line, `#` starts a
+    /// comment.
+    /// Addresses are of `chain`, or of the Bitcoin network their prefixes
+    /// name when it is `None`.
+    pub fn load(path: &Path, chain: Option<&Chain>) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("cannot read targets file {}", path.display()))?;
+        Self::parse(&text, chain)
+            .with_context(|| format!("invalid targets file {}", path.display()))
+    }
+
+    /// Parse one address or descriptor per line, as [`TargetSet::load`]
+    /// does. Blank lines and everything after a `#` are ignored, except for
+    /// the `#` right after a descriptor's `)` that starts its checksum;
+    /// errors name the offending line.
+    pub fn parse(s: &str, chain: Option<&Chain>) -> Result<Self> {
+        s.lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let comment = line
+                    .match_indices('#')
+                    .find(|(i, _)| !line[..*i].ends_with(')'))
+                    .map_or(line.len(), |(i, _)| i);
+                let addr = line[..comment].trim();
+                (!addr.is_empty()).then_some((i + 1, addr))
+            })
+            .map(|(line, addr)| {
+                chain
+                    .map_or_else(|| addr.parse(), |chain| Target::parse(addr, chain))
+                    .with_context(|| format!("line {line}: {addr:?}"))
+            })
+            .collect()
+    }
+
+    /// Number of distinct targets.
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Targets in [`Target`] order.
+    pub fn iter(&self) -> impl Iterator<Item = &Target> {
+        self.targets.iter()
+    }
+
+    /// The targets committing to `hash160`.
+    pub fn matching(&self, hash160: &[u8; 20]) -> &[Target] {
+        let lo = self
+            .targets
+            .partition_point(|t| t.hash160().is_some_and(|h| h < hash160));
+        let hi = lo + self.targets[lo..].partition_point(|t| t.hash160() == Some(hash160));
+        &self.targets[lo..hi]
+    }
+
+    pub fn contains(&self, hash160: &[u8; 20]) -> bool {
+        !self.matching(hash160).is_empty()
+    }
+
+    /// The targets paid to by a public key whose HASH160, in `compressed`
+    /// or uncompressed form, is `key_hash`.
+    pub fn paid_by(&self, key_hash: &[u8; 20], compressed: bool) -> Vec<&Target> {
+        let mut found: Vec<&Target> = self
+            .matching(key_hash)
+            .iter()
+            .filter(|t| !t.is_nested_segwit() && t.accepts(compressed))
+            .collect();
+        if compressed && self.nested > 0 {
+            let script_hash = hash160(&p2wpkh_script(key_hash));
+            found.extend(
+                self.matching(&script_hash)
+                    .iter()
+                    .filter(|t| t.is_nested_segwit()),
+            );
+        }
+        found
+    }
+
+    /// Whether [`TargetSet::paid_by`] is non-empty.
+    pub fn contains_key_hash(&self, key_hash: &[u8; 20], compressed: bool) -> bool {
+        !self.paid_by(key_hash, compressed).is_empty()
+    }
+
+    /// Whether any target is a [`AddressKind::P2shP2wpkh`] address.
+    pub fn has_nested_segwit(&self) -> bool {
+        self.nested > 0
+    }
+
+    /// The Taproot targets paying to `output_key`.
+    pub fn taproot(&self, output_key: &[u8; 32]) -> &[Target] {
+        let lo = self
+            .targets
+            .partition_point(|t| t.output_key().is_none_or(|k| k < output_key));
+        let hi = lo + self.targets[lo..].partition_point(|t| t.output_key() == Some(output_key));
+        &self.targets[lo..hi]
+    }
+
+    /// Whether any target is a [`Target::Taproot`] address. These sort last.
+    pub fn has_taproot(&self) -> bool {
+        matches!(self.targets.last(), Some(Target::Taproot { .. }))
+    }
+
+    /// The Ethereum target with `address`, if there is one.
+    pub fn ethereum(&self, address: &[u8; 20]) -> Option<&Target> {
+        let target = Target::Ethereum { address: *address };
+        self.targets
+            .binary_search(&target)
+            .ok()
+            .map(|i| &self.targets[i])
+    }
+
+    /// Whether any target is a [`Target::Ethereum`] address. These sort
+    /// right after the HASH160 targets.
+    pub fn has_ethereum(&self) -> bool {
+        let i = self
+            .targets
+            .partition_point(|t| matches!(t, Target::Hash160 { .. }));
+        matches!(self.targets.get(i), Some(Target::Ethereum { .. }))
+    }
+
+    /// The Nostr target with x-only `pubkey`, if there is one.
+    pub fn nostr(&self, pubkey: &[u8; 32]) -> Option<&Target> {
+        let target = Target::Nostr { pubkey: *pubkey };
+        self.targets
+            .binary_search(&target)
+            .ok()
+            .map(|i| &self.targets[i])
+    }
+
+    /// Whether any target is a [`Target::Nostr`] key. These sort right
+    /// after the Ethereum targets.
+    pub fn has_nostr(&self) -> bool {
+        let i = self
+            .targets
+            .partition_point(|t| matches!(t, Target::Hash160 { .. } | Target::Ethereum { .. }));
+        matches!(self.targets.get(i), Some(Target::Nostr { .. }))
+    }
+
+    /// The public key targets for the point whose compressed serialization
+    /// is `key`.
+    pub fn pubkey(&self, key: &[u8; 33]) -> &[Target] {
+        let lo = self.targets.partition_point(|t| match t {
+            Target::PubKey { key: k, .. } => k < key,
+            Target::Taproot { .. } => false,
+            _ => true,
+        });
+        let hi = lo
+            + self.targets[lo..]
+                .partition_point(|t| matches!(t, Target::PubKey { key: k, .. } if k == key));
+        &self.targets[lo..hi]
+    }
+
+    /// Whether any target is a [`Target::PubKey`]. These sort right before
+    /// the Taproot targets.
+    pub fn has_pubkey(&self) -> bool {
+        let i = self.targets.partition_point(|t| {
+            matches!(
+                t,
+                Target::Hash160 { .. } | Target::Ethereum { .. } | Target::Nostr { .. }
+            )
+        });
+        matches!(self.targets.get(i), Some(Target::PubKey { .. }))
+    }
+
+    /// Whether every target is a [`Target::Hash160`], the only kind a
+    /// HASH160 of the public key can match.
+    pub fn hash160_only(&self) -> bool {
+        self.targets
+            .last()
+            .is_none_or(|t| matches!(t, Target::Hash160 { .. }))
+    }
+
+    /// Add `target`; returns false if it was already present.
+    pub fn insert(&mut self, target: Target) -> bool {
+        match self.targets.binary_search(&target) {
+            Ok(_) => false,
+            Err(i) => {
+                self.targets.insert(i, target);
+                self.nested += usize::from(target.is_nested_segwit());
+                true
+            }
+        }
+    }
+
+    /// Remove `target`; returns false if it was not present.
+    pub fn remove(&mut self, target: &Target) -> bool {
+        match self.targets.binary_search(target) {
+            Ok(i) => {
+                self.targets.remove(i);
+                self.nested -= usize::from(target.is_nested_segwit());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// The distinct HASH160s of the [`Target::Hash160`] targets, in order.
+    pub fn hash160s(&self) -> Vec<[u8; 20]> {
+        let mut hashes: Vec<_> = self
+            .targets
+            .iter()
+            .filter_map(Target::hash160)
+            .copied()
+            .collect();
+        hashes.dedup();
+        hashes
+    }
+
+    /// SHA-256 over the sorted targets, each as a byte naming its variant
+    /// followed by its key material, address kind and chain; identifies the
+    /// set in checkpoints.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut sha = Sha256::new();
+        for target in &self.targets {
+            match target {
+                Target::Hash160 {
+                    hash160,
+                    kind,
+                    chain,
+                } => {
+                    sha.update([0]);
+                    sha.update(hash160);
+                    sha.update([*kind as u8]);
+                    digest_chain(&mut sha, chain);
+                }
+                Target::Ethereum { address } => {
+                    sha.update([1]);
+                    sha.update(address);
+                }
+                Target::Nostr { pubkey } => {
+                    sha.update([2]);
+                    sha.update(pubkey);
+                }
+                Target::PubKey {
+                    key,
+                    compressed,
+                    chain,
+                } => {
+                    sha.update([3]);
+                    sha.update(key);
+                    sha.update([u8::from(*compressed)]);
+                    digest_chain(&mut sha, chain);
+                }
+                Target::Taproot { output_key, chain } => {
+                    sha.update([4]);
+                    sha.update(output_key);
+                    digest_chain(&mut sha, chain);
+                }
+            }
+        }
+        sha.finalize().into()
+    }
+}
+
+/// Feed `chain` to a [`TargetSet::digest`]: its version bytes, then its
+/// lowercase HRP prefixed with its length, which is 0 for no HRP.
+fn digest_chain(sha: &mut Sha256, chain: &Chain) {
+    sha.update([chain.p2pkh_version, chain.p2sh_version, chain.wif_prefix]);
+    let hrp = chain.hrp.map(|hrp| hrp.to_lowercase()).unwrap_or_default();
+    sha.update([hrp.len() as u8]);
+    sha.update(hrp);
+}
+
+impl From<Target> for TargetSet {
+    fn from(target: Target) -> Self {
+        [target].into_iter().collect()
+    }
+}
+
+impl FromIterator<Target> for TargetSet {
+    fn from_iter<I: IntoIterator<Item = Target>>(iter: I) -> Self {
+        let mut targets: Vec<Target> = iter.into_iter().collect();
+        targets.sort_unstable();
+        targets.dedup();
+        let nested = targets.iter().filter(|t| t.is_nested_segwit()).count();
+        Self { targets, nested }
+    }
+}
+
+impl FromStr for TargetSet {
+    type Err = anyhow::Error;
+
+    /// Parse Bitcoin addresses one per line; see [`TargetSet::parse`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_set_parses_lines_with_comments() {
+        let set: TargetSet = "# puzzle addresses\n\
+             19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA\n\
+             \n\
+             1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH  # key 1\n\
+             19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA\n\
+             pkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)#e48zzw02 # key 1\n"
+            .parse()
+            .unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.has_pubkey());
+        let key1: Target = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".parse().unwrap();
+        assert!(set.contains(key1.hash160().unwrap()));
+        assert!(!set.contains(&[0; 20]));
+
+        let err = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH\nnot-an-address\n"
+            .parse::<TargetSet>()
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("line 2"), "{err:#}");
+        let err =
+            "pkh(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)#e48zzw03"
+                .parse::<TargetSet>()
+                .unwrap_err();
+        assert!(format!("{err:#}").contains("checksum mismatch"), "{err:#}");
+    }
+
+    #[test]
+    fn target_set_digest_ignores_order_and_duplicates() {
+        let a: Target = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".parse().unwrap();
+        let b: Target = "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA".parse().unwrap();
+        let ab: TargetSet = [a, b].into_iter().collect();
+        let ba: TargetSet = [b, a, b].into_iter().collect();
+        assert_eq!(ab, ba);
+        assert_eq!(ab.digest(), ba.digest());
+        assert_ne!(ab.digest(), TargetSet::from(a).digest());
+
+        // The same HASH160 as another address kind, or on another chain, is
+        // another search.
+        let h160 = *a.hash160().unwrap();
+        let digest = |target| TargetSet::from(target).digest();
+        assert_ne!(
+            digest(a),
+            digest(Target::new(AddressKind::P2shP2wpkh, h160))
+        );
+        let litecoin = crate::chain::Chains::default().get("litecoin").unwrap();
+        assert_ne!(
+            digest(a),
+            digest(Target::Hash160 {
+                hash160: h160,
+                kind: AddressKind::P2pkh,
+                chain: litecoin,
+            })
+        );
+    }
+
+    #[test]
+    fn target_parses_and_displays_p2wpkh() {
+        let t: Target = "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4"
+            .parse()
+            .unwrap();
+        let p2pkh: Target = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".parse().unwrap();
+        let h160 = *p2pkh.hash160().unwrap();
+        assert_eq!(t, Target::new(AddressKind::P2wpkh, h160));
+        assert_eq!(t.to_string(), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        assert!("bc1qnot-an-address".parse::<Target>().is_err());
+
+        // Both kinds are kept, and the digest tells them apart.
+        let set: TargetSet = [t, p2pkh].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.matching(&h160), &[p2pkh, t]);
+        assert_ne!(set.digest(), TargetSet::from(p2pkh).digest());
+        assert_ne!(TargetSet::from(t).digest(), TargetSet::from(p2pkh).digest());
+    }
+
+    #[test]
+    fn target_infers_the_network_or_takes_the_given_chain() {
+        let regtest: Target = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"
+            .parse()
+            .unwrap();
+        assert_eq!(regtest.chain(), Some(&Network::Regtest.chain()));
+        let testnet: Target = "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r".parse().unwrap();
+        assert_eq!(testnet.chain(), Some(&Network::Testnet.chain()));
+        assert_eq!(testnet.hash160(), regtest.hash160());
+        assert!(Target::parse("mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r", &Chain::BITCOIN).is_err());
+
+        // Base58 prefixes are shared by every test network; HRPs are not.
+        let regtest_chain = Network::Regtest.chain();
+        let moved = Target::parse("mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r", &regtest_chain).unwrap();
+        assert_eq!(moved.chain(), Some(&regtest_chain));
+        assert_eq!(moved.to_string(), testnet.to_string());
+        let signet = Network::Signet.chain();
+        assert!(Target::parse(&regtest.to_string(), &signet).is_err());
+
+        let litecoin = crate::chain::Chains::default().get("litecoin").unwrap();
+        let set =
+            TargetSet::parse("LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ\n", Some(&litecoin)).unwrap();
+        assert_eq!(set.iter().next().unwrap().hash160(), testnet.hash160());
+        let err = TargetSet::parse(
+            "# Bitcoin\n1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+            Some(&litecoin),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+    }
+
+    #[test]
+    fn target_parses_and_displays_p2tr() {
+        let addr = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9";
+        let t: Target = addr.to_uppercase().parse().unwrap();
+        assert!(matches!(t, Target::Taproot { .. }));
+        assert_eq!(t.to_string(), addr);
+        assert_eq!(t.hash160(), None);
+
+        // Taproot targets sort after every HASH160 target.
+        let p2pkh: Target = "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA".parse().unwrap();
+        let set: TargetSet = [t, p2pkh].into_iter().collect();
+        assert!(set.has_taproot());
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [p2pkh, t]);
+        assert_eq!(set.hash160s(), [*p2pkh.hash160().unwrap()]);
+        assert_ne!(set.digest(), TargetSet::from(p2pkh).digest());
+    }
+
+    #[test]
+    fn target_parses_and_displays_ethereum() {
+        let addr = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+        let t: Target = addr.to_lowercase().parse().unwrap();
+        assert!(matches!(t, Target::Ethereum { .. }));
+        assert_eq!(t.to_string(), addr);
+        assert_eq!(t.chain(), None);
+        assert!(Target::parse(&addr.replace('E', "e"), &Chain::BITCOIN).is_err());
+
+        // Ethereum targets sort between the HASH160 and Taproot targets.
+        let p2pkh: Target = "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA".parse().unwrap();
+        let p2tr: Target = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9"
+            .parse()
+            .unwrap();
+        let set: TargetSet = [p2tr, t, p2pkh].into_iter().collect();
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [p2pkh, t, p2tr]);
+        assert!(set.has_ethereum() && set.has_taproot() && !set.hash160_only());
+        assert!(!TargetSet::from(p2tr).has_ethereum());
+        assert!(TargetSet::from(p2pkh).hash160_only());
+        let without_eth: TargetSet = [p2tr, p2pkh].into_iter().collect();
+        assert_ne!(set.digest(), without_eth.digest());
+    }
+
+    #[test]
+    fn target_parses_and_displays_nostr_keys() {
+        let npub = "npub1lycg5qvjtrp3qjf5f7zl382j9x6nrjz9sdhenvyxq8c3808qxmus6gq266";
+        let t: Target = npub.parse().unwrap();
+        assert_eq!(
+            t,
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(t.to_string(), npub);
+        assert_eq!(t.chain(), None);
+        let err = "ff".repeat(32).parse::<Target>().unwrap_err();
+        assert!(
+            err.to_string().contains("not an x-only public key"),
+            "{err}"
+        );
+
+        // Nostr keys sort between the Ethereum and Taproot targets.
+        let eth: Target = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+            .parse()
+            .unwrap();
+        let p2tr: Target = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9"
+            .parse()
+            .unwrap();
+        let set: TargetSet = [p2tr, t, eth].into_iter().collect();
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [eth, t, p2tr]);
+        assert!(set.has_nostr() && set.has_ethereum() && set.has_taproot());
+        assert!(!TargetSet::from(eth).has_nostr());
+        assert!(TargetSet::from(t).has_nostr());
+    }
+
+    #[test]
+    fn target_parses_public_keys_in_either_form() {
+        let compressed = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                            483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+        let c: Target = compressed.parse().unwrap();
+        let u: Target = uncompressed.to_uppercase().parse().unwrap();
+        assert_eq!(c.to_string(), compressed);
+        assert_eq!(u.to_string(), uncompressed);
+        assert_eq!(c.address(), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+        assert_eq!(u.address(), "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+        assert_eq!(c.chain(), Some(&Chain::BITCOIN));
+        let err = format!("04{}", "00".repeat(64))
+            .parse::<Target>()
+            .unwrap_err();
+        assert!(err.to_string().contains("not a valid public key"), "{err}");
+
+        // Both forms are the same point; they sort before Taproot targets.
+        let p2tr: Target = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9"
+            .parse()
+            .unwrap();
+        let set: TargetSet = [p2tr, c, u].into_iter().collect();
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [u, c, p2tr]);
+        assert!(set.has_pubkey() && !set.has_nostr());
+        let Target::PubKey { key, .. } = c else {
+            panic!("{c:?}")
+        };
+        assert_eq!(set.pubkey(&key), &[u, c]);
+        assert_ne!(
+            set.digest(),
+            [p2tr, c].into_iter().collect::<TargetSet>().digest()
+        );
+    }
+
+    #[test]
+    fn target_from_script_recognizes_each_output_type() {
+        let key_hash = "751e76e8199196d454941c45d1b3a323f1433bd6";
+        for (script, addr) in [
+            (
+                format!("76a914{key_hash}88ac"),
+                "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+            ),
+            (
+                format!("0014{key_hash}"),
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                "a914bcfeb728b584253d5f3f70bcb780e9ef218a68f487".to_owned(),
+                "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN",
+            ),
+            (
+                "5120da4710964f7852695de2da025290e24af6d8c281de5a0b902b7135fd9fd74d21".to_owned(),
+                "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+            ),
+        ] {
+            let target = Target::from_script(&hex::decode(&script).unwrap(), &Chain::BITCOIN);
+            assert_eq!(target.unwrap(), addr.parse().unwrap(), "{script}");
+        }
+
+        let p2wsh = hex::decode(format!("0020{}", "ab".repeat(32))).unwrap();
+        let err = Target::from_script(&p2wsh, &Chain::BITCOIN).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("not a P2PKH, P2WPKH, P2SH or P2TR"),
+            "{err}"
+        );
+        let truncated = hex::decode(format!("76a914{key_hash}88")).unwrap();
+        assert!(Target::from_script(&truncated, &Chain::BITCOIN).is_err());
+        let dogecoin = crate::chain::Chains::default().get("dogecoin").unwrap();
+        let p2wpkh = hex::decode(format!("0014{key_hash}")).unwrap();
+        assert!(Target::from_script(&p2wpkh, &dogecoin).is_err());
+    }
+
+    #[test]
+    fn target_reads_mixed_case_hrp_prefixes_as_base58() {
+        // A Namecoin P2PKH address whose prefix spells the `nc` HRP.
+        let namecoin = crate::chain::Chains::default().get("namecoin").unwrap();
+        let target = Target::parse("NC1k7g82eayxvRtsrW9rTLFXjc9cjRfWfb", &namecoin).unwrap();
+        assert_eq!(
+            target.hash160().map(hex::encode).as_deref(),
+            Some("a9569433e8115c603811a2267aa50c9b46e06760")
+        );
+        assert!(matches!(
+            target,
+            Target::Hash160 {
+                kind: AddressKind::P2pkh,
+                ..
+            }
+        ));
+        assert_eq!(target.to_string(), "NC1k7g82eayxvRtsrW9rTLFXjc9cjRfWfb");
+        assert!(Target::parse("NC1k7g82eayxvRtsrW9rTLFXjc9cjRfWfc", &namecoin).is_err());
+    }
+
+    #[test]
+    fn segwit_targets_are_unreachable_without_compressed_keys() {
+        for (addr, reachable) in [
+            ("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", true),
+            ("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", false),
+            ("3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN", false),
+            (
+                "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+                false,
+            ),
+            ("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf", true),
+            (
+                "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                true,
+            ),
+        ] {
+            let target: Target = addr.parse().unwrap();
+            assert_eq!(
+                target.reachable(PubkeyFormat::Uncompressed),
+                reachable,
+                "{addr}"
+            );
+            assert!(target.reachable(PubkeyFormat::Compressed), "{addr}");
+            assert!(target.reachable(PubkeyFormat::Both), "{addr}");
+        }
+    }
+
+    #[test]
+    fn target_parses_descriptors_of_each_type() {
+        let key = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        for (desc, addr) in [
+            (format!("pkh({key})"), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"),
+            (
+                format!("pkh([d34db33f/44'/0'/0']{key})"),
+                "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+            ),
+            (
+                format!("wpkh({key})"),
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                format!("sh(wpkh({key}))#jqtwwlah"),
+                "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN",
+            ),
+            (
+                format!("tr({})#gxjkeue2", &key[2..]),
+                "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+            ),
+            (
+                format!("tr({key})"),
+                "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+            ),
+            (
+                "addr(bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4)#uyjndxcw".to_owned(),
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                "addr(mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r)".to_owned(),
+                "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r",
+            ),
+        ] {
+            let target: Target = desc.parse().unwrap();
+            assert_eq!(target.address(), addr, "{desc}");
+            if !desc.starts_with("pkh") {
+                assert_eq!(target, addr.parse().unwrap(), "{desc}");
+            }
+        }
+
+        // `pkh` keys are compared as points, keeping the form they came in.
+        let uncompressed = PublicKey::from_str(key).unwrap().serialize_uncompressed();
+        let pkh: Target = format!("pkh({})", hex::encode(uncompressed))
+            .parse()
+            .unwrap();
+        assert_eq!(pkh, hex::encode(uncompressed).parse().unwrap());
+        assert_eq!(pkh.address(), "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+
+        for (desc, expected) in [
+            (format!("pkh({key})#e48zzw03"), "checksum mismatch"),
+            (
+                format!("wpkh({})", hex::encode(uncompressed)),
+                "takes a compressed public key",
+            ),
+            (format!("sh(pkh({key}))"), "must wrap wpkh()"),
+            (format!("combo({key})"), "unsupported descriptor combo()"),
+            ("pkh(xpub661MyMwAqRbcF)".to_owned(), "not a hex public key"),
+            (format!("pkh(03{})", &key[2..64]), "not a valid public key"),
+            (
+                "addr(0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf)".to_owned(),
+                "addr() takes",
+            ),
+            (format!("addr(pkh({key}))"), "addr() takes"),
+        ] {
+            let err = desc.parse::<Target>().unwrap_err();
+            assert!(err.to_string().contains(expected), "{desc}: {err}");
+        }
+        let dogecoin = crate::chain::Chains::default().get("dogecoin").unwrap();
+        for desc in [format!("wpkh({key})"), format!("sh(wpkh({key}))")] {
+            let err = Target::parse(&desc, &dogecoin).unwrap_err();
+            assert!(
+                err.to_string().contains("no SegWit addresses"),
+                "{desc}: {err}"
+            );
+        }
+    }
+}
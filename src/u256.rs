@@ -0,0 +1,679 @@
+//! Unsigned 256-bit integer used for keyspace arithmetic.
+//!
+//! Stored as four little-endian `u64` limbs. [`U256::to_le_words`] produces
+//! the eight little-endian `u32` limbs the GPU kernel expects in
+//! [`crate::gpu::Params`] (limb 0 is least significant).
+
+use anyhow::{Result, anyhow};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Rem, Shl, Shr, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Unsigned 256-bit integer.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct U256([u64; 4]);
+
+impl U256 {
+    pub const ZERO: Self = Self([0; 4]);
+    pub const ONE: Self = Self([1, 0, 0, 0]);
+    pub const MAX: Self = Self([u64::MAX; 4]);
+    pub const BITS: u32 = 256;
+
+    /// Build from little-endian `u64` limbs.
+    pub const fn from_limbs(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    /// Little-endian `u64` limbs.
+    pub const fn limbs(&self) -> [u64; 4] {
+        self.0
+    }
+
+    pub const fn from_u64(v: u64) -> Self {
+        Self([v, 0, 0, 0])
+    }
+
+    pub const fn from_u128(v: u128) -> Self {
+        Self([v as u64, (v >> 64) as u64, 0, 0])
+    }
+
+    /// Build from eight little-endian `u32` limbs (the GPU layout).
+    pub fn from_le_words(w: [u32; 8]) -> Self {
+        let mut limbs = [0u64; 4];
+        for (i, l) in limbs.iter_mut().enumerate() {
+            *l = (w[2 * i + 1] as u64) << 32 | w[2 * i] as u64;
+        }
+        Self(limbs)
+    }
+
+    /// Eight little-endian `u32` limbs (the GPU layout).
+    pub fn to_le_words(&self) -> [u32; 8] {
+        let mut w = [0u32; 8];
+        for (i, l) in self.0.iter().enumerate() {
+            w[2 * i] = *l as u32;
+            w[2 * i + 1] = (*l >> 32) as u32;
+        }
+        w
+    }
+
+    /// Build from 32 big-endian bytes.
+    pub fn from_be_bytes(be: [u8; 32]) -> Self {
+        let mut limbs = [0u64; 4];
+        for (i, l) in limbs.iter_mut().enumerate() {
+            let j = (3 - i) * 8;
+            *l = u64::from_be_bytes(be[j..j + 8].try_into().expect("8 bytes"));
+        }
+        Self(limbs)
+    }
+
+    /// 32 big-endian bytes.
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut be = [0u8; 32];
+        for (i, l) in self.0.iter().enumerate() {
+            let j = (3 - i) * 8;
+            be[j..j + 8].copy_from_slice(&l.to_be_bytes());
+        }
+        be
+    }
+
+    /// 32 little-endian bytes (the byte layout of the GPU output buffer).
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        let mut le = [0u8; 32];
+        for (i, l) in self.0.iter().enumerate() {
+            le[i * 8..i * 8 + 8].copy_from_slice(&l.to_le_bytes());
+        }
+        le
+    }
+
+    /// Parse a big-endian hex string. Accepts an optional `0x`/`0X` prefix
+    /// and `_` separators.
+    pub fn from_hex(s: &str) -> Result<Self> {
+        let s = s.trim();
+        // strip 0x/0X if present
+        let s = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        // allow underscores in hex for readability
+        let mut s = s.replace('_', "");
+
+        if s.is_empty() {
+            return Err(anyhow!("empty hex"));
+        }
+        // hex::decode needs an even number of nibbles
+        if s.len() % 2 == 1 {
+            s.insert(0, '0'); // left-pad one zero to make it even-length
+        }
+
+        let bytes = hex::decode(&s)?;
+        if bytes.len() > 32 {
+            return Err(anyhow!("hex too large (>256 bits)"));
+        }
+
+        // big-endian -> fixed 32 bytes
+        let mut be = [0u8; 32];
+        be[32 - bytes.len()..].copy_from_slice(&bytes);
+        Ok(Self::from_be_bytes(be))
+    }
+
+    /// Parse a decimal string. Accepts `_` separators.
+    pub fn from_dec(s: &str) -> Result<Self> {
+        let s = s.trim().replace('_', "");
+        if s.is_empty() {
+            return Err(anyhow!("empty decimal"));
+        }
+        let mut v = Self::ZERO;
+        for c in s.chars() {
+            let d = c
+                .to_digit(10)
+                .ok_or_else(|| anyhow!("invalid decimal digit {c:?}"))?;
+            v = v
+                .checked_mul_u64(10)
+                .and_then(|v| v.checked_add(Self::from_u64(d as u64)))
+                .ok_or_else(|| anyhow!("decimal too large (>256 bits)"))?;
+        }
+        Ok(v)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    /// The least significant 64 bits.
+    pub fn low_u64(&self) -> u64 {
+        self.0[0]
+    }
+
+    /// The least significant 128 bits.
+    pub fn low_u128(&self) -> u128 {
+        (self.0[1] as u128) << 64 | self.0[0] as u128
+    }
+
+    /// The value as a `u64`, if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        (self.bits() <= 64).then_some(self.0[0])
+    }
+
+    /// The value as a `u128`, if it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        (self.bits() <= 128).then_some(self.low_u128())
+    }
+
+    /// Nearest `f64`, for rates and ratios where precision does not matter.
+    pub fn to_f64(&self) -> f64 {
+        self.0.iter().rev().fold(0.0, |acc, &limb| {
+            acc * 18_446_744_073_709_551_616.0 + limb as f64
+        })
+    }
+
+    /// Number of significant bits (0 for zero).
+    pub fn bits(&self) -> u32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return 64 * i as u32 + (64 - self.0[i].leading_zeros());
+            }
+        }
+        0
+    }
+
+    /// Value of bit `i` (0 = least significant).
+    pub fn bit(&self, i: u32) -> bool {
+        i < 256 && (self.0[(i / 64) as usize] >> (i % 64)) & 1 == 1
+    }
+
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for (i, o) in out.iter_mut().enumerate() {
+            let (s1, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (s2, c2) = s1.overflowing_add(carry as u64);
+            *o = s2;
+            carry = c1 || c2;
+        }
+        (Self(out), carry)
+    }
+
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let mut out = [0u64; 4];
+        let mut borrow = false;
+        for (i, o) in out.iter_mut().enumerate() {
+            let (d1, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (d2, b2) = d1.overflowing_sub(borrow as u64);
+            *o = d2;
+            borrow = b1 || b2;
+        }
+        (Self(out), borrow)
+    }
+
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        // Schoolbook multiplication into 8 limbs; anything above limb 3 overflows.
+        let mut wide = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = self.0[i] as u128 * rhs.0[j] as u128 + wide[i + j] as u128 + carry;
+                wide[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            wide[i + 4] = carry as u64;
+        }
+        let overflow = wide[4..].iter().any(|&l| l != 0);
+        (Self([wide[0], wide[1], wide[2], wide[3]]), overflow)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rem(rhs).map(|(q, _)| q)
+    }
+
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rem(rhs).map(|(_, r)| r)
+    }
+
+    pub fn checked_add_u64(self, rhs: u64) -> Option<Self> {
+        self.checked_add(Self::from_u64(rhs))
+    }
+
+    pub fn checked_sub_u64(self, rhs: u64) -> Option<Self> {
+        self.checked_sub(Self::from_u64(rhs))
+    }
+
+    pub fn checked_mul_u64(self, rhs: u64) -> Option<Self> {
+        self.checked_mul(Self::from_u64(rhs))
+    }
+
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).unwrap_or(Self::ZERO)
+    }
+
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).unwrap_or(Self::MAX)
+    }
+
+    pub fn wrapping_add(self, rhs: Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(self, rhs: Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+
+    pub fn wrapping_mul(self, rhs: Self) -> Self {
+        self.overflowing_mul(rhs).0
+    }
+
+    /// Quotient and remainder, or `None` when dividing by zero.
+    pub fn checked_div_rem(self, rhs: Self) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            return None;
+        }
+        if let Some(d) = rhs.to_u64() {
+            let (q, r) = self.div_rem_u64(d);
+            return Some((q, Self::from_u64(r)));
+        }
+        if self < rhs {
+            return Some((Self::ZERO, self));
+        }
+        // Binary long division, starting at the dividend's top bit.
+        let mut q = Self::ZERO;
+        let mut r = Self::ZERO;
+        for i in (0..self.bits()).rev() {
+            r = r << 1;
+            if self.bit(i) {
+                r.0[0] |= 1;
+            }
+            if r >= rhs {
+                r = r.wrapping_sub(rhs);
+                q.0[(i / 64) as usize] |= 1 << (i % 64);
+            }
+        }
+        Some((q, r))
+    }
+
+    /// Quotient and remainder by a `u64`. Panics if `d` is zero.
+    pub fn div_rem_u64(self, d: u64) -> (Self, u64) {
+        assert!(d != 0, "attempt to divide by zero");
+        let mut q = [0u64; 4];
+        let mut r = 0u128;
+        for i in (0..4).rev() {
+            let cur = r << 64 | self.0[i] as u128;
+            q[i] = (cur / d as u128) as u64;
+            r = cur % d as u128;
+        }
+        (Self(q), r as u64)
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Most significant limb first
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(v: u64) -> Self {
+        Self::from_u64(v)
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(v: u128) -> Self {
+        Self::from_u128(v)
+    }
+}
+
+impl From<[u32; 8]> for U256 {
+    fn from(w: [u32; 8]) -> Self {
+        Self::from_le_words(w)
+    }
+}
+
+impl From<U256> for [u32; 8] {
+    fn from(v: U256) -> Self {
+        v.to_le_words()
+    }
+}
+
+macro_rules! impl_checked_op {
+    ($trait:ident, $method:ident, $checked:ident, $msg:literal) => {
+        impl $trait for U256 {
+            type Output = U256;
+            fn $method(self, rhs: U256) -> U256 {
+                self.$checked(rhs).expect($msg)
+            }
+        }
+
+        impl $trait<u64> for U256 {
+            type Output = U256;
+            fn $method(self, rhs: u64) -> U256 {
+                self.$checked(U256::from_u64(rhs)).expect($msg)
+            }
+        }
+    };
+}
+
+impl_checked_op!(Add, add, checked_add, "attempt to add with overflow");
+impl_checked_op!(Sub, sub, checked_sub, "attempt to subtract with overflow");
+impl_checked_op!(Mul, mul, checked_mul, "attempt to multiply with overflow");
+impl_checked_op!(Div, div, checked_div, "attempt to divide by zero");
+impl_checked_op!(
+    Rem,
+    rem,
+    checked_rem,
+    "attempt to calculate the remainder with a divisor of zero"
+);
+
+impl AddAssign for U256 {
+    fn add_assign(&mut self, rhs: U256) {
+        *self = *self + rhs;
+    }
+}
+
+impl AddAssign<u64> for U256 {
+    fn add_assign(&mut self, rhs: u64) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for U256 {
+    fn sub_assign(&mut self, rhs: U256) {
+        *self = *self - rhs;
+    }
+}
+
+impl SubAssign<u64> for U256 {
+    fn sub_assign(&mut self, rhs: u64) {
+        *self = *self - rhs;
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = U256;
+
+    /// Logical left shift; bits shifted past 256 are discarded.
+    fn shl(self, n: u32) -> U256 {
+        if n >= 256 {
+            return U256::ZERO;
+        }
+        let limbs = (n / 64) as usize;
+        let bits = n % 64;
+        let mut out = [0u64; 4];
+        for i in (limbs..4).rev() {
+            let src = i - limbs;
+            out[i] = self.0[src] << bits;
+            if bits != 0 && src > 0 {
+                out[i] |= self.0[src - 1] >> (64 - bits);
+            }
+        }
+        U256(out)
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = U256;
+
+    /// Logical right shift.
+    fn shr(self, n: u32) -> U256 {
+        if n >= 256 {
+            return U256::ZERO;
+        }
+        let limbs = (n / 64) as usize;
+        let bits = n % 64;
+        let mut out = [0u64; 4];
+        for (i, o) in out.iter_mut().enumerate().take(4 - limbs) {
+            let src = i + limbs;
+            *o = self.0[src] >> bits;
+            if bits != 0 && src + 1 < 4 {
+                *o |= self.0[src + 1] << (64 - bits);
+            }
+        }
+        U256(out)
+    }
+}
+
+impl FromStr for U256 {
+    type Err = anyhow::Error;
+
+    /// Decimal by default; hex when prefixed with `0x`/`0X`.
+    fn from_str(s: &str) -> Result<Self> {
+        let t = s.trim();
+        if t.starts_with("0x") || t.starts_with("0X") {
+            Self::from_hex(t)
+        } else {
+            Self::from_dec(t)
+        }
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+        // Peel off 19 decimal digits at a time.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut parts = Vec::new();
+        let mut v = *self;
+        while !v.is_zero() {
+            let (q, r) = v.div_rem_u64(CHUNK);
+            parts.push(r);
+            v = q;
+        }
+        let mut s = parts.pop().expect("non-zero").to_string();
+        for p in parts.iter().rev() {
+            s.push_str(&format!("{p:019}"));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl fmt::Debug for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "U256({self:#x})")
+    }
+}
+
+impl fmt::LowerHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = hex::encode(self.to_be_bytes());
+        let s = s.trim_start_matches('0');
+        f.pad_integral(true, "0x", if s.is_empty() { "0" } else { s })
+    }
+}
+
+impl fmt::UpperHex for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = hex::encode_upper(self.to_be_bytes());
+        let s = s.trim_start_matches('0');
+        f.pad_integral(true, "0x", if s.is_empty() { "0" } else { s })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_be_bytes_converts_to_le_words() {
+        let mut be = [0u8; 32];
+        for (i, b) in be.iter_mut().enumerate() {
+            *b = (i + 1) as u8;
+        }
+        let v = U256::from_be_bytes(be);
+        assert_eq!(
+            v.to_le_words(),
+            [
+                0x1d1e_1f20,
+                0x191a_1b1c,
+                0x1516_1718,
+                0x1112_1314,
+                0x0d0e_0f10,
+                0x090a_0b0c,
+                0x0506_0708,
+                0x0102_0304,
+            ]
+        );
+        assert_eq!(v.to_be_bytes(), be);
+        let mut le = be;
+        le.reverse();
+        assert_eq!(v.to_le_bytes(), le);
+        assert_eq!(U256::from_le_words(v.to_le_words()), v);
+    }
+
+    #[test]
+    fn from_hex_handles_basic_cases() {
+        assert_eq!(
+            U256::from_hex("1").expect("hex").to_le_words(),
+            [1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        let words = U256::from_hex("abc").expect("hex").to_le_words();
+        assert_eq!(words[0], 0x0abc);
+        assert!(words[1..].iter().all(|&w| w == 0));
+        assert_eq!(U256::from_hex("0x1_0000").unwrap(), U256::from_u64(0x10000));
+        assert!(U256::from_hex("").is_err());
+        assert!(U256::from_hex(&"1".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn add_propagates_carry() {
+        let a = U256::from_le_words([u32::MAX, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!((a + 1).to_le_words(), [0, 1, 0, 0, 0, 0, 0, 0]);
+        let b = U256::from_limbs([u64::MAX, u64::MAX, 0, 0]);
+        assert_eq!(b + 1, U256::from_limbs([0, 0, 1, 0]));
+        assert_eq!(U256::MAX.checked_add_u64(1), None);
+        assert_eq!(U256::MAX.saturating_add(U256::ONE), U256::MAX);
+        assert_eq!(U256::MAX.wrapping_add(U256::ONE), U256::ZERO);
+    }
+
+    #[test]
+    fn sub_handles_borrow() {
+        assert_eq!(U256::from_u64(5) - 3, U256::from_u64(2));
+        let (r, borrow) = U256::ZERO.overflowing_sub(U256::ONE);
+        assert_eq!(r, U256::MAX);
+        assert!(borrow);
+        assert_eq!(U256::ZERO.checked_sub(U256::ONE), None);
+        assert_eq!(U256::ZERO.saturating_sub(U256::ONE), U256::ZERO);
+        assert_eq!(
+            U256::from_limbs([0, 0, 1, 0]) - 1,
+            U256::from_limbs([u64::MAX, u64::MAX, 0, 0])
+        );
+    }
+
+    #[test]
+    fn ord_compares_most_significant_limb_first() {
+        let a = U256::from_u64(1);
+        let b = U256::from_u64(2);
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(b.cmp(&a), Ordering::Greater);
+        assert_eq!(a.cmp(&U256::from_u64(1)), Ordering::Equal);
+        assert!(
+            U256::from_limbs([0, 0, 0, 1]) > U256::from_limbs([u64::MAX, u64::MAX, u64::MAX, 0])
+        );
+    }
+
+    #[test]
+    fn low_u64_extracts_least_significant_bits() {
+        let x = U256::from_le_words([0x89ab_cdef, 0x0123_4567, 5, 0, 0, 0, 0, 0]);
+        assert_eq!(x.low_u64(), 0x0123_4567_89ab_cdef);
+        assert_eq!(x.to_u64(), None);
+        assert_eq!(x.to_u128(), Some(5 << 64 | 0x0123_4567_89ab_cdef));
+    }
+
+    #[test]
+    fn mul_div_rem_round_trip() {
+        let a = U256::from_hex("123456789abcdef0fedcba9876543210").unwrap();
+        let b = U256::from_hex("fedcba98765432100123456789").unwrap();
+        let p = a * b;
+        assert_eq!(p / b, a);
+        assert_eq!(p % b, U256::ZERO);
+        let (q, r) = (p + 7).checked_div_rem(a).unwrap();
+        assert_eq!((q, r), (b, U256::from_u64(7)));
+        assert_eq!(U256::MAX.checked_mul_u64(2), None);
+        assert_eq!(U256::MAX.saturating_mul(U256::from_u64(2)), U256::MAX);
+        assert_eq!(U256::ONE.checked_div(U256::ZERO), None);
+        assert_eq!(U256::from_u64(100).div_rem_u64(7), (U256::from_u64(14), 2));
+        assert_eq!(U256::from_u64(100) % 7, U256::from_u64(2));
+    }
+
+    #[test]
+    fn to_f64_approximates_value() {
+        assert_eq!(U256::ZERO.to_f64(), 0.0);
+        assert_eq!(U256::from_u64(12345).to_f64(), 12345.0);
+        assert_eq!((U256::ONE << 200).to_f64(), 2f64.powi(200));
+        assert_eq!(U256::MAX.to_f64(), 2f64.powi(256));
+    }
+
+    #[test]
+    fn shifts_and_bits() {
+        assert_eq!(U256::ONE << 255, U256::from_limbs([0, 0, 0, 1 << 63]));
+        assert_eq!((U256::ONE << 255) >> 255, U256::ONE);
+        assert_eq!(U256::ONE << 256, U256::ZERO);
+        assert_eq!(
+            U256::from_u64(0xff) << 60,
+            U256::from_limbs([0xf << 60, 0xf, 0, 0])
+        );
+        assert_eq!(
+            U256::from_limbs([0xf << 60, 0xf, 0, 0]) >> 60,
+            U256::from_u64(0xff)
+        );
+        assert_eq!(U256::ZERO.bits(), 0);
+        assert_eq!(U256::ONE.bits(), 1);
+        assert_eq!((U256::ONE << 64).bits(), 65);
+        assert_eq!(U256::MAX.bits(), 256);
+    }
+
+    #[test]
+    fn string_round_trips() {
+        let dec = "115792089237316195423570985008687907852837564279074904382605163141518161494337";
+        let n: U256 = dec.parse().unwrap();
+        assert_eq!(n.to_string(), dec);
+        assert_eq!(
+            format!("{n:x}"),
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+        );
+        assert_eq!("0xff".parse::<U256>().unwrap(), U256::from_u64(255));
+        assert_eq!(format!("{:#x}", U256::from_u64(255)), "0xff");
+        assert_eq!(format!("{:X}", U256::from_u64(255)), "FF");
+        assert_eq!(U256::ZERO.to_string(), "0");
+        assert_eq!(format!("{:x}", U256::ZERO), "0");
+        assert_eq!(
+            U256::from_u64(10_000_000_000_000_000_000).to_string(),
+            "10000000000000000000"
+        );
+        assert!("12a".parse::<U256>().is_err());
+        assert!(U256::MAX.to_string().parse::<U256>().unwrap() == U256::MAX);
+        assert!(format!("{}0", U256::MAX).parse::<U256>().is_err());
+    }
+}
@@ -1,4 +1,8 @@
 use assert_cmd::Command;
+use gpu_bitcrack::checkpoint::Checkpoint;
+use gpu_bitcrack::u256::U256;
+use gpu_bitcrack::{PubkeyFormat, Target, TargetSet};
+use predicates::prelude::*;
 use predicates::str::contains;
 use rstest::rstest;
 use serial_test::file_serial;
@@ -22,14 +26,636 @@ use serial_test::file_serial;
 #[case("8000:ffff", "1BDyrQ6WoF8VN3g9SAS1iKZcPzFfnDVieY")]
 #[case("10000:1ffff", "1HduPEXZRdG26SUT5Yk83mLkPyjnZuJ7Bm")]
 #[case("20000:3ffff", "1GnNTmTVLZiqQfLbAdp9DVdicEnB5GoERE")]
-#[ignore] // Heavy GPU/CPU test; run manually: cargo test -- --ignored --nocapture
-#[file_serial(gpu)] // all tests with the same name run one-at-a-time across binaries
 fn finds_known_address(#[case] range: &str, #[case] target: &str) {
     let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
-    cmd.arg(range).arg(target).arg("--batch").arg("1000000"); // 1 million candidates per GPU dispatch
+    cmd.arg(range)
+        .arg(target)
+        .arg("--backend")
+        .arg("cpu")
+        .arg("--batch")
+        .arg("65536");
+
+    cmd.assert()
+        .success()
+        .stdout(contains("FOUND!"))
+        .stdout(contains(target));
+}
+
+#[rstest]
+#[case("1:1", "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH")]
+#[case("20000:3ffff", "1GnNTmTVLZiqQfLbAdp9DVdicEnB5GoERE")]
+#[case(
+    "1:ffff",
+    "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9"
+)]
+#[case("1:ffff", "0x2B5AD5c4795c026514f8317c7a215E218DcCD6cF")]
+#[ignore] // Needs a GPU adapter; run manually: cargo test -- --ignored --nocapture
+#[file_serial(gpu)] // all tests with the same name run one-at-a-time across binaries
+fn finds_known_address_gpu(#[case] range: &str, #[case] target: &str) {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.arg(range)
+        .arg(target)
+        .arg("--backend")
+        .arg("gpu")
+        .arg("--batch")
+        .arg("1000000"); // 1 million candidates per GPU dispatch
 
     cmd.assert()
         .success()
         .stdout(contains("FOUND!"))
         .stdout(contains(target));
 }
+
+#[test]
+fn reports_miss_on_cpu() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args([
+        "1:ff",
+        "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF",
+        "--backend",
+        "cpu",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(contains("Not found in the given range."));
+}
+
+#[test]
+fn finds_every_address_in_targets_file() {
+    let path =
+        std::env::temp_dir().join(format!("gpu-bitcrack-targets-{}.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "# puzzles 3, 5 and 6\n\
+         19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA\n\
+         1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k  # 0x15\n\
+         \n\
+         1PitScNLyp2HCygzadCh7FveTnfmpPbfp8\n",
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(args)
+            .args(["--backend", "cpu", "--targets-file"])
+            .arg(&path);
+        cmd.assert().success()
+    };
+    let out = run(&["1:3f", "--batch", "16"])
+        .stdout(contains("19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA"))
+        .stdout(contains("1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k"))
+        .stdout(contains("1PitScNLyp2HCygzadCh7FveTnfmpPbfp8"))
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).unwrap();
+    assert_eq!(stdout.matches("FOUND!").count(), 3, "{stdout}");
+    assert!(!stdout.contains("Not found"), "{stdout}");
+
+    // The positional target joins the file; only it and puzzle 3 lie in 1:f.
+    run(&["1:f", "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH", "--batch", "16"])
+        .stdout(contains("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"))
+        .stdout(contains("2 of 4 targets found"))
+        .stdout(contains("missing  : 1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k"))
+        .stdout(contains("missing  : 1PitScNLyp2HCygzadCh7FveTnfmpPbfp8"))
+        .stdout(contains("Not found").not());
+    // None of them lie in 40:7f.
+    run(&["40:7f", "--batch", "16"])
+        .stdout(contains("Not found in the given range."))
+        .stdout(contains("missing").not());
+
+    // All three lie in one batch, which is reported in full and in key order.
+    let out = run(&["1:3f", "--batch", "64", "--stop-after", "1"])
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).unwrap();
+    let addresses: Vec<&str> = stdout
+        .lines()
+        .filter_map(|l| l.strip_prefix("address  : "))
+        .collect();
+    assert_eq!(
+        addresses,
+        [
+            "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA",
+            "1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k",
+            "1PitScNLyp2HCygzadCh7FveTnfmpPbfp8",
+        ]
+    );
+    let out = run(&["1:3f", "--batch", "16", "--stop-after", "1"])
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).unwrap();
+    assert_eq!(stdout.matches("FOUND!").count(), 1, "{stdout}");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn finds_uncompressed_address_with_matching_wif() {
+    let run = |format: &str| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:f", "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm"])
+            .args(["--backend", "cpu", "--pubkey-format", format]);
+        cmd.assert().success()
+    };
+    for format in ["uncompressed", "both"] {
+        run(format)
+            .stdout(contains("FOUND!"))
+            .stdout(contains("form     : uncompressed"))
+            .stdout(contains(
+                "wif      : 5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf",
+            ));
+    }
+    run("compressed").stdout(contains("Not found in the given range."));
+}
+
+#[test]
+fn finds_native_segwit_address() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args(["1:f", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"])
+        .args(["--backend", "cpu"]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "address  : bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        ))
+        .stdout(contains(
+            "wif      : KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn",
+        ));
+}
+
+#[test]
+fn finds_nested_segwit_address_and_prints_redeem_script() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args(["1:f", "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN"])
+        .args(["--backend", "cpu"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("address  : 3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN"))
+        .stdout(contains(
+            "redeem   : 0014751e76e8199196d454941c45d1b3a323f1433bd6",
+        ));
+}
+
+#[test]
+fn finds_taproot_address() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args([
+        "1:f",
+        "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+    ])
+    .args(["--backend", "cpu"]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "address  : bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+        ))
+        .stdout(contains(
+            "wif      : KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn",
+        ));
+}
+
+#[test]
+fn finds_ethereum_address_and_prints_raw_key() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args(["1:f", "0x2b5ad5c4795c026514f8317c7a215e218dccd6cf"])
+        .args(["--backend", "cpu"]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "address  : 0x2B5AD5c4795c026514f8317c7a215E218DcCD6cF",
+        ))
+        .stdout(contains(
+            "priv_hex : 0000000000000000000000000000000000000000000000000000000000000002",
+        ))
+        .stdout(contains("wif").not());
+}
+
+#[test]
+fn finds_nostr_key_and_prints_nsec() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args([
+        "1:f",
+        "npub1lycg5qvjtrp3qjf5f7zl382j9x6nrjz9sdhenvyxq8c3808qxmus6gq266",
+    ])
+    .args(["--backend", "cpu"]);
+    cmd.assert()
+        .success()
+        .stdout(contains(
+            "nsec     : nsec1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqps52s3re",
+        ))
+        .stdout(contains("wif").not());
+}
+
+#[test]
+fn finds_public_key_and_prints_its_address() {
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args([
+        "1:f",
+        "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+         483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+    ])
+    .args(["--backend", "cpu"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("address  : 1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm"))
+        .stdout(contains("form     : uncompressed"))
+        .stdout(contains(
+            "wif      : 5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf",
+        ));
+}
+
+#[test]
+fn finds_raw_hash160_and_script_targets() {
+    let run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:f", "--backend", "cpu"]).args(args);
+        cmd.assert()
+    };
+    let out = run(&[
+        "--target-hash160",
+        "751e76e8199196d454941c45d1b3a323f1433bd6",
+        "--target-script",
+        "5120da4710964f7852695de2da025290e24af6d8c281de5a0b902b7135fd9fd74d21",
+        "--target-script",
+        "a914bcfeb728b584253d5f3f70bcb780e9ef218a68f487",
+    ])
+    .success()
+    .stdout(contains("address  : 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"))
+    .stdout(contains(
+        "address  : bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9",
+    ))
+    .stdout(contains("address  : 3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN"))
+    .get_output()
+    .stdout
+    .clone();
+    let stdout = String::from_utf8(out).unwrap();
+    assert_eq!(stdout.matches("FOUND!").count(), 3, "{stdout}");
+
+    run(&["--target-script", "0020abcd"])
+        .failure()
+        .stderr(contains("not a P2PKH, P2WPKH, P2SH or P2TR script"));
+    run(&["--target-hash160", "751e76e8"])
+        .failure()
+        .stderr(contains("not 20 hex-encoded bytes"));
+}
+
+#[test]
+fn finds_descriptor_targets_and_prints_descriptor_with_wif() {
+    let key = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    let run = |target: &str, pubkeys: &str| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args([
+            "1:f",
+            target,
+            "--backend",
+            "cpu",
+            "--pubkey-format",
+            pubkeys,
+        ]);
+        cmd.assert()
+    };
+    run(&format!("sh(wpkh({key}))#jqtwwlah"), "compressed")
+        .success()
+        .stdout(contains("address  : 3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN"))
+        .stdout(contains(
+            "desc     : sh(wpkh(KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn))#3xm2u094",
+        ));
+    run(&format!("tr({})", &key[2..]), "compressed")
+        .success()
+        .stdout(contains(
+            "desc     : tr(KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn)#efdxzarj",
+        ));
+    run("addr(1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm)", "both")
+        .success()
+        .stdout(contains(
+            "desc     : pkh(5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf)#vxzgs9na",
+        ));
+    run(&format!("wpkh({key})#gul0776m"), "compressed")
+        .failure()
+        .stderr(contains("descriptor checksum mismatch"));
+}
+
+#[test]
+fn descriptor_keys_match_whatever_pubkey_format_is_searched() {
+    let run = |target: &str, pubkeys: &str| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args([
+            "1:f",
+            target,
+            "--backend",
+            "cpu",
+            "--pubkey-format",
+            pubkeys,
+        ]);
+        cmd.assert()
+    };
+    // An uncompressed pkh() key is found without searching uncompressed keys.
+    run(
+        "pkh(0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+         483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8)",
+        "compressed",
+    )
+    .success()
+    .stdout(contains("address  : 1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm"))
+    .stdout(contains(
+        "desc     : pkh(5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf)#vxzgs9na",
+    ));
+    // SegWit outputs can never match an uncompressed key.
+    run(
+        "tr(79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+        "uncompressed",
+    )
+    .failure()
+    .stderr(contains(
+        "only pays to compressed public keys, which --pubkey-format uncompressed never derives",
+    ));
+    run("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", "uncompressed")
+        .failure()
+        .stderr(contains("only pays to compressed public keys"));
+}
+
+#[test]
+fn finds_regtest_address_with_test_network_wif() {
+    let run = |network: Option<&str>| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:f", "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"])
+            .args(["--backend", "cpu"]);
+        if let Some(network) = network {
+            cmd.args(["--network", network]);
+        }
+        cmd.assert()
+    };
+    for network in [None, Some("regtest")] {
+        run(network)
+            .success()
+            .stdout(contains(
+                "address  : bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+            ))
+            .stdout(contains(
+                "wif      : cMahea7zqjxrtgAbB7LSGbcQUr1uX1ojuat9jZodMN87JcbXMTcA",
+            ));
+    }
+    run(Some("mainnet"))
+        .failure()
+        .stderr(contains("not a mainnet address"));
+}
+
+#[test]
+fn finds_altcoin_addresses_with_chain_prefixes() {
+    let run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:f", "--backend", "cpu"]).args(args);
+        cmd.assert()
+    };
+    run(&["LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ", "--chain", "litecoin"])
+        .success()
+        .stdout(contains("address  : LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ"))
+        .stdout(contains(
+            "wif      : T33ydQRKp4FCW5LCLLUB7deioUMoveiwekdwUwyfRDeGZm76aUjV",
+        ));
+
+    // Dogecoin's prefixes under another name.
+    let path =
+        std::env::temp_dir().join(format!("gpu-bitcrack-chains-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "[mycoin]\np2pkh_version = 0x1e\np2sh_version = 0x16\nwif_prefix = 0x9e\n",
+    )
+    .unwrap();
+    let chains_file = path.to_str().unwrap();
+    run(&[
+        "DFpN6QqFfUm3gKNaxN6tNcab1FArL9cZLE",
+        "--chain",
+        "mycoin",
+        "--chains-file",
+        chains_file,
+    ])
+    .success()
+    .stdout(contains(
+        "wif      : QNcdLVw8fHkixm6NNyN6nVwxKek4u7qrioRbQmjxac5TVoTtZuot",
+    ));
+    run(&["DFpN6QqFfUm3gKNaxN6tNcab1FArL9cZLE", "--chain", "mycoin"])
+        .failure()
+        .stderr(contains("unknown chain \"mycoin\""));
+    run(&["LVuDpNCSSj6pQ7t9Pv6d6sUkLKoqDEVUnJ", "--chain", "dash"])
+        .failure()
+        .stderr(contains("not a dash address"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// A fresh path in the temp dir for a checkpoint file.
+fn checkpoint_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "gpu-bitcrack-{name}-{}.checkpoint",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn saves_checkpoint_at_end_of_range() {
+    let path = checkpoint_path("save");
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args(["1:fff", "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF"])
+        .args(["--backend", "cpu", "--batch", "256"])
+        .arg("--checkpoint")
+        .arg(&path);
+    cmd.assert().success();
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("version = 4\n"), "{saved}");
+    assert!(saved.contains("keyspace = 1:fff\n"), "{saved}");
+    assert!(saved.contains("last_verified = fff\n"), "{saved}");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn saves_checkpoint_when_target_is_found() {
+    let path = checkpoint_path("found");
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args(["1:fff", "1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe"])
+        .args(["--backend", "cpu", "--batch", "256"])
+        .arg("--checkpoint")
+        .arg(&path);
+    cmd.assert().success().stdout(contains("FOUND!"));
+
+    // Saved through the end of the batch holding the hit, long before the
+    // checkpoint interval elapses.
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("last_verified = 300\n"), "{saved}");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn resume_skips_verified_keys_and_checks_parameters() {
+    let path = checkpoint_path("resume");
+    // The key behind 1LeBZP5... (0x202) lies in the already-verified prefix
+    // 1..=7ff, so a resumed search must not find it.
+    let target: Target = "1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe".parse().unwrap();
+    Checkpoint {
+        keyspace: "1:fff".parse().unwrap(),
+        targets_digest: TargetSet::from(target).digest(),
+        pubkeys: PubkeyFormat::Compressed,
+        batch: 256,
+        last_verified: U256::from_u64(0x7ff),
+        found: Vec::new(),
+    }
+    .save(&path)
+    .unwrap();
+
+    let run = |target: &str| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:fff", target])
+            .args(["--backend", "cpu", "--batch", "256", "--resume"])
+            .arg("--checkpoint")
+            .arg(&path);
+        cmd.assert()
+    };
+    run("1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe")
+        .success()
+        .stdout(contains("Not found in the given range."));
+    run("1PgQVLmst3Z314JrQn5TNiys8Hc38TcXJu")
+        .failure()
+        .stderr(contains("does not match"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn resume_counts_hits_saved_in_the_checkpoint() {
+    let path = checkpoint_path("hits");
+    let targets = std::env::temp_dir().join(format!(
+        "gpu-bitcrack-resume-targets-{}.txt",
+        std::process::id()
+    ));
+    // Puzzles 10 (0x202) and 12 (0xa7b).
+    std::fs::write(
+        &targets,
+        "1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe\n1DBaumZxUkM4qMQRt2LVWyFJq5kDtSZQot\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:fff", "--backend", "cpu", "--batch", "256"])
+            .arg("--targets-file")
+            .arg(&targets)
+            .arg("--checkpoint")
+            .arg(&path)
+            .args(args);
+        cmd.assert().success()
+    };
+    run(&["--stop-after", "1"])
+        .stdout(contains("address  : 1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe"))
+        .stdout(contains("1DBaumZxUkM4qMQRt2LVWyFJq5kDtSZQot").not());
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("last_verified = 300\n"), "{saved}");
+    assert!(
+        saved.contains("found = 202 1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe\n"),
+        "{saved}"
+    );
+
+    // The saved hit is reported again and alone reaches --stop-after 1.
+    run(&["--resume", "--stop-after", "1"])
+        .stdout(contains("address  : 1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe"))
+        .stdout(contains("1DBaumZxUkM4qMQRt2LVWyFJq5kDtSZQot").not());
+    // It also counts as found, so finding the other target ends the search.
+    run(&["--resume"])
+        .stdout(contains("address  : 1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe"))
+        .stdout(contains("address  : 1DBaumZxUkM4qMQRt2LVWyFJq5kDtSZQot"))
+        .stdout(contains("targets found").not());
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        saved.contains("found = 202 1LeBZP5QCwwgXRtmVUvTVrraqPUokyLHqe\n"),
+        "{saved}"
+    );
+    assert!(
+        saved.contains("found = a7b 1DBaumZxUkM4qMQRt2LVWyFJq5kDtSZQot\n"),
+        "{saved}"
+    );
+
+    // A saved key must still match its target.
+    let tampered = saved.replace("found = a7b", "found = a7c");
+    std::fs::write(&path, tampered).unwrap();
+    let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+    cmd.args(["1:fff", "--backend", "cpu", "--batch", "256", "--resume"])
+        .arg("--targets-file")
+        .arg(&targets)
+        .arg("--checkpoint")
+        .arg(&path);
+    cmd.assert().failure().stderr(contains(
+        "a7c does not match 1DBaumZxUkM4qMQRt2LVWyFJq5kDtSZQot",
+    ));
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&targets).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn sigint_finishes_batch_and_saves_checkpoint() {
+    use std::process::{Command as StdCommand, Stdio};
+    use std::time::Duration;
+
+    let path = checkpoint_path("sigint");
+    let child = StdCommand::new(assert_cmd::cargo::cargo_bin("gpu-bitcrack"))
+        .args(["1:ffffffffff", "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF"])
+        .args([
+            "--backend",
+            "cpu",
+            "--batch",
+            "4096",
+            "--checkpoint-interval",
+            "3600",
+        ])
+        .arg("--checkpoint")
+        .arg(&path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(1500));
+    let killed = StdCommand::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+
+    let out = child.wait_with_output().unwrap();
+    assert_eq!(out.status.code(), Some(130));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("Interrupted."), "{stdout}");
+    let last = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("last verified key : "))
+        .expect("last verified key line")
+        .to_owned();
+    // Whole batches only: the verified prefix ends just before a batch start.
+    let last_key = u64::from_str_radix(&last, 16).unwrap();
+    assert_eq!(last_key % 4096, 0);
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        saved.contains(&format!("last_verified = {last}\n")),
+        "{saved}"
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn prints_progress_unless_quiet() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::cargo_bin("gpu-bitcrack").unwrap();
+        cmd.args(["1:3fff", "1FeexV6bAHb8ybZjqQMjJrcCrHGW9sb6uF"])
+            .args(["--backend", "cpu", "--batch", "4096"])
+            .args(extra);
+        cmd.assert().success()
+    };
+    run(&["--progress-interval", "0"])
+        .stderr(contains("key 0x1000 | 4.10 Kkeys | 25.00% |"))
+        .stderr(contains("key 0x3fff | 16.38 Kkeys | 100.00% |"))
+        .stderr(contains("keys/s (avg "))
+        .stderr(contains("ETA 00:00:00"));
+    run(&["--progress-interval", "0", "--quiet"]).stderr("");
+}
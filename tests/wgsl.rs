@@ -1,6 +1,28 @@
 //! Ensure that every WGSL shader parses and validates on the CPU.
 
 use std::fs;
+use std::path::Path;
+
+/// Source of `path` with the files named on a leading `// requires:` line
+/// prepended, the same way the host concatenates kernels.
+fn composed_source(path: &Path) -> String {
+    let src = fs::read_to_string(path).expect("read WGSL file");
+    let mut out = String::new();
+    if let Some(deps) = src
+        .lines()
+        .next()
+        .and_then(|l| l.strip_prefix("// requires:"))
+    {
+        for dep in deps.split_whitespace() {
+            out.push_str(
+                &fs::read_to_string(Path::new("shaders").join(dep)).expect("read dependency"),
+            );
+            out.push('\n');
+        }
+    }
+    out.push_str(&src);
+    out
+}
 
 #[test]
 fn wgsl_files_compile() {
@@ -10,10 +32,12 @@ fn wgsl_files_compile() {
             continue;
         }
 
-        let src = fs::read_to_string(&path).expect("read WGSL file");
+        let src = composed_source(&path);
 
         // Parse WGSL source using Naga (CPU implementation of WGSL frontend)
-        let module = naga::front::wgsl::parse_str(&src).expect("WGSL parse");
+        let module = naga::front::wgsl::parse_str(&src).unwrap_or_else(|e| {
+            panic!("WGSL parse {}: {}", path.display(), e.emit_to_string(&src))
+        });
 
         // Validate the module – catches type or binding errors without requiring a GPU device
         let mut validator = naga::valid::Validator::new(
@@ -21,6 +45,8 @@ fn wgsl_files_compile() {
             naga::valid::Capabilities::all(),
         );
 
-        validator.validate(&module).expect("WGSL validation");
+        validator
+            .validate(&module)
+            .unwrap_or_else(|e| panic!("WGSL validation {}: {:?}", path.display(), e));
     }
 }